@@ -83,6 +83,7 @@ impl ToString for LiteralExpr {
             Literal::String(s) => s.to_string(),
             Literal::Boolean(b) => b.to_string(),
             Literal::Nil => "Nil".to_string(),
+            _ => self.value.to_string(),
         };
 
         format!("({fm})")
@@ -114,11 +115,45 @@ impl ToString for Grouping {
 
 //------------------------------------------
 
+#[derive(Clone)]
+pub struct Tuple {
+    pub paren: Token,
+    pub elements: Vec<Expr>,
+}
+
+impl Tuple {
+    pub fn new(paren: Token, elements: Vec<Expr>) -> Self {
+        Self { paren, elements }
+    }
+
+    pub fn make_expr(paren: Token, elements: Vec<Expr>) -> Expr {
+        Expr::Tuple(Box::new(Self::new(paren, elements)))
+    }
+}
+
+impl ToString for Tuple {
+    fn to_string(&self) -> String {
+        let elements: Vec<String> = self.elements.iter().map(|e| e.pretty_print()).collect();
+        format!("(tuple {})", elements.join(" "))
+    }
+}
+
 //------------------------------------------
 #[derive(Clone)]
 pub struct Variable {
-    name: Token,
+    pub name: Token,
+}
+
+impl Variable {
+    pub fn new(name: Token) -> Self {
+        Self { name }
+    }
+
+    pub fn make_expr(name: Token) -> Expr {
+        Expr::Var(Box::new(Self::new(name)))
+    }
 }
+
 impl Display for Variable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)
@@ -126,6 +161,266 @@ impl Display for Variable {
 }
 //------------------------------------------
 
+#[derive(Clone)]
+pub struct Assign {
+    pub target: Pattern,
+    pub value: Box<Expr>,
+}
+
+impl Assign {
+    pub fn new(target: Pattern, value: Box<Expr>) -> Self {
+        Self { target, value }
+    }
+
+    pub fn make_expr(target: Pattern, value: Expr) -> Expr {
+        Expr::Assign(Box::new(Self::new(target, Box::new(value))))
+    }
+}
+
+impl ToString for Assign {
+    fn to_string(&self) -> String {
+        format!("(= {} {})", self.target, self.value)
+    }
+}
+
+//------------------------------------------
+
+#[derive(Clone)]
+pub struct Call {
+    pub callee: Box<Expr>,
+    pub paren: Token,
+    pub arguments: Vec<Expr>,
+}
+
+impl Call {
+    pub fn new(callee: Box<Expr>, paren: Token, arguments: Vec<Expr>) -> Self {
+        Self {
+            callee,
+            paren,
+            arguments,
+        }
+    }
+
+    pub fn make_expr(callee: Expr, paren: Token, arguments: Vec<Expr>) -> Expr {
+        Expr::Call(Box::new(Self::new(Box::new(callee), paren, arguments)))
+    }
+}
+
+impl ToString for Call {
+    fn to_string(&self) -> String {
+        let arguments: Vec<String> = self.arguments.iter().map(|a| a.pretty_print()).collect();
+        format!("(call {} {})", self.callee, arguments.join(" "))
+    }
+}
+
+//------------------------------------------
+
+// `optional` is set for `object?.name`, which yields nil instead of failing
+// when the object is nil
+#[derive(Clone)]
+pub struct Get {
+    pub object: Box<Expr>,
+    pub name: Token,
+    pub optional: bool,
+}
+
+impl Get {
+    pub fn new(object: Box<Expr>, name: Token, optional: bool) -> Self {
+        Self {
+            object,
+            name,
+            optional,
+        }
+    }
+
+    pub fn make_expr(object: Expr, name: Token, optional: bool) -> Expr {
+        Expr::Get(Box::new(Self::new(Box::new(object), name, optional)))
+    }
+}
+
+impl ToString for Get {
+    fn to_string(&self) -> String {
+        let operator = if self.optional { "?." } else { "." };
+        format!("({operator} {} {})", self.object, self.name)
+    }
+}
+
+//------------------------------------------
+
+// `object[index]` or `object?[index]`
+#[derive(Clone)]
+pub struct Index {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+    pub optional: bool,
+}
+
+impl Index {
+    pub fn new(object: Box<Expr>, bracket: Token, index: Box<Expr>, optional: bool) -> Self {
+        Self {
+            object,
+            bracket,
+            index,
+            optional,
+        }
+    }
+
+    pub fn make_expr(object: Expr, bracket: Token, index: Expr, optional: bool) -> Expr {
+        Expr::Index(Box::new(Self::new(Box::new(object), bracket, Box::new(index), optional)))
+    }
+}
+
+impl ToString for Index {
+    fn to_string(&self) -> String {
+        let operator = if self.optional { "?[]" } else { "[]" };
+        format!("({operator} {} {})", self.object, self.index)
+    }
+}
+
+//------------------------------------------
+
+// a chain of calls, property accesses and indexing that contains at least one
+// `?.` or `?[`, if any of those meets nil the rest of the chain is skipped and
+// the whole chain evaluates to nil
+#[derive(Clone)]
+pub struct OptionalChain {
+    pub expression: Box<Expr>,
+}
+
+impl OptionalChain {
+    pub fn new(expression: Box<Expr>) -> Self {
+        Self { expression }
+    }
+
+    pub fn make_expr(expression: Expr) -> Expr {
+        Expr::OptionalChain(Box::new(Self::new(Box::new(expression))))
+    }
+}
+
+impl ToString for OptionalChain {
+    fn to_string(&self) -> String {
+        self.expression.pretty_print()
+    }
+}
+
+//------------------------------------------
+
+// `start..end` or `start..=end`, followed by an optional `step expr`
+#[derive(Clone)]
+pub struct RangeExpr {
+    pub start: Box<Expr>,
+    pub operator: Token,
+    pub end: Box<Expr>,
+    pub step: Option<Box<Expr>>,
+}
+
+impl RangeExpr {
+    pub fn new(start: Box<Expr>, operator: Token, end: Box<Expr>, step: Option<Box<Expr>>) -> Self {
+        Self {
+            start,
+            operator,
+            end,
+            step,
+        }
+    }
+
+    pub fn make_expr(start: Expr, operator: Token, end: Expr, step: Option<Expr>) -> Expr {
+        Expr::Range(Box::new(Self::new(Box::new(start), operator, Box::new(end), step.map(Box::new))))
+    }
+}
+
+impl ToString for RangeExpr {
+    fn to_string(&self) -> String {
+        match &self.step {
+            Some(step) => format!("({} {} {} {})", self.operator, self.start, self.end, step),
+            None => format!("({} {} {})", self.operator, self.start, self.end),
+        }
+    }
+}
+
+//------------------------------------------
+
+// `...expr`, only allowed as a call argument or a tuple element where the
+// values of the spread tuple are inserted in its place
+#[derive(Clone)]
+pub struct Spread {
+    pub ellipsis: Token,
+    pub expression: Box<Expr>,
+}
+
+impl Spread {
+    pub fn new(ellipsis: Token, expression: Box<Expr>) -> Self {
+        Self {
+            ellipsis,
+            expression,
+        }
+    }
+
+    pub fn make_expr(ellipsis: Token, expression: Expr) -> Expr {
+        Expr::Spread(Box::new(Self::new(ellipsis, Box::new(expression))))
+    }
+}
+
+impl ToString for Spread {
+    fn to_string(&self) -> String {
+        format!("(... {})", self.expression)
+    }
+}
+
+//------------------------------------------
+
+// the left hand side of a declaration or assignment, either a single name or
+// a (possibly nested) tuple of names that a tuple value is destructured into
+#[derive(Clone)]
+pub enum Pattern {
+    Name(Token),
+    Tuple(Token, Vec<Pattern>),
+}
+
+impl Pattern {
+    pub fn get_line(&self) -> usize {
+        match self {
+            Pattern::Name(t) => t.get_line(),
+            Pattern::Tuple(paren, _) => paren.get_line(),
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Name(name) => write!(f, "{name}"),
+            Pattern::Tuple(_, patterns) => {
+                let inner: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+                write!(f, "({})", inner.join(", "))
+            }
+        }
+    }
+}
+
+// an optional `: Type` after the pattern of a declaration, type names are
+// only looked at by the type checker, the interpreter ignores them
+#[derive(Clone)]
+pub enum TypeAnnotation {
+    Named(Token),
+    Tuple(Token, Vec<TypeAnnotation>),
+}
+
+impl Display for TypeAnnotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeAnnotation::Named(name) => write!(f, "{name}"),
+            TypeAnnotation::Tuple(_, annotations) => {
+                let inner: Vec<String> = annotations.iter().map(|a| a.to_string()).collect();
+                write!(f, "({})", inner.join(", "))
+            }
+        }
+    }
+}
+
+//------------------------------------------
+
 #[derive(Clone)]
 pub enum Expr {
     Binary(Box<Binary>),
@@ -133,6 +428,14 @@ pub enum Expr {
     Literal(LiteralExpr),
     Grouping(Box<Grouping>),
     Var(Box<Variable>),
+    Assign(Box<Assign>),
+    Tuple(Box<Tuple>),
+    Call(Box<Call>),
+    Get(Box<Get>),
+    Spread(Box<Spread>),
+    Index(Box<Index>),
+    OptionalChain(Box<OptionalChain>),
+    Range(Box<RangeExpr>),
 }
 
 impl Expr {
@@ -143,6 +446,14 @@ impl Expr {
             Expr::Literal(l) => l.to_string(),
             Expr::Grouping(g) => g.to_string(),
             Expr::Var(v) => v.to_string(),
+            Expr::Assign(a) => a.to_string(),
+            Expr::Tuple(t) => t.to_string(),
+            Expr::Call(c) => c.to_string(),
+            Expr::Get(g) => g.to_string(),
+            Expr::Spread(s) => s.to_string(),
+            Expr::Index(i) => i.to_string(),
+            Expr::OptionalChain(o) => o.to_string(),
+            Expr::Range(r) => r.to_string(),
         }
     }
 }
@@ -0,0 +1,439 @@
+use crate::ast::{Expr, Pattern, TypeAnnotation};
+use crate::statements::{EnumVariant, Statement};
+use crate::token::{Literal, Token, TokenType};
+use anyhow::Error;
+use std::collections::HashMap;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TypeError {
+    #[error("[{0}]: Cannot apply {1} to {2} and {3}")]
+    BinaryMismatch(usize, String, Type, Type),
+    #[error("[{0}]: Cannot apply {1} to {2}")]
+    UnaryMismatch(usize, String, Type),
+    #[error("[{0}]: '{1}' is declared as {2} but assigned {3}")]
+    AnnotationMismatch(usize, String, Type, Type),
+    #[error("[{0}]: Unknown type {1}")]
+    UnknownType(usize, String),
+    #[error("[{0}]: Cannot destructure {1}, expected a tuple")]
+    NotDestructurable(usize, Type),
+    #[error("[{0}]: Pattern expects {1} values but the tuple has {2}")]
+    ArityMismatch(usize, usize, usize),
+    #[error("[{0}]: Cannot spread {1}, expected a tuple, range or map")]
+    NotSpreadable(usize, Type),
+    #[error("[{0}]: Range bounds and step must be numbers, got {1}")]
+    InvalidRangeBound(usize, Type),
+}
+
+// the static type of an expression, `Any` is used wherever the checker cannot
+// tell and is compatible with everything so unannotated code stays dynamic
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Any,
+    Nil,
+    Num,
+    Str,
+    Bool,
+    Range,
+    Tuple(Vec<Type>),
+    // a value of a declared enum
+    Enum(String),
+    // the enum itself and its variant constructors
+    EnumObject(String),
+    Constructor(String),
+}
+
+impl Type {
+    fn of_literal(literal: &Literal) -> Self {
+        match literal {
+            Literal::Number(_) => Type::Num,
+            Literal::String(_) => Type::Str,
+            Literal::Boolean(_) => Type::Bool,
+            Literal::Nil => Type::Nil,
+            Literal::Tuple(elements) => Type::Tuple(elements.iter().map(Type::of_literal).collect()),
+            Literal::Range(_) => Type::Range,
+            _ => Type::Any,
+        }
+    }
+
+    fn accepts(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Tuple(a), Type::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.accepts(b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    // the same grouping of types `Interpreter::type_checkable` uses for
+    // == and !=
+    fn comparable(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Tuple(_), Type::Tuple(_)) | (Type::Enum(_), Type::Enum(_)) => true,
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Any => write!(f, "Any"),
+            Type::Nil => write!(f, "Nil"),
+            Type::Num => write!(f, "Num"),
+            Type::Str => write!(f, "Str"),
+            Type::Bool => write!(f, "Bool"),
+            Type::Range => write!(f, "Range"),
+            Type::Tuple(types) => {
+                let inner: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+                write!(f, "({})", inner.join(", "))
+            }
+            Type::Enum(name) => write!(f, "{name}"),
+            Type::EnumObject(name) => write!(f, "enum {name}"),
+            Type::Constructor(name) => write!(f, "constructor of {name}"),
+        }
+    }
+}
+
+// what the checker knows about a variable, `declared` is the annotation if
+// there was one and `current` the type of the value it holds right now
+#[derive(Clone)]
+struct Binding {
+    declared: Option<Type>,
+    current: Type,
+}
+
+// static pass behind `brainrot check`, infers the type of every expression it
+// can and reports operations that are certain to fail at runtime
+pub struct TypeChecker {
+    variables: HashMap<String, Binding>,
+    // enum name -> (variant name, whether the variant has fields)
+    enums: HashMap<String, Vec<(String, bool)>>,
+    errors: Vec<Error>,
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        Self {
+            variables: HashMap::new(),
+            enums: HashMap::new(),
+            errors: vec![],
+        }
+    }
+
+    pub fn get_errors(&self) -> &Vec<Error> {
+        &self.errors
+    }
+
+    pub fn check(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Expression(e) | Statement::Print(e) => {
+                self.infer(e);
+            }
+            Statement::Var(pattern, annotation, e, _) | Statement::Const(pattern, annotation, e, _) => {
+                let value = self.infer(e);
+                let declared = annotation.as_ref().and_then(|a| self.resolve_annotation(a));
+                self.declare(pattern, declared, value);
+            }
+            Statement::Enum(name, variants, _) => self.declare_enum(name, variants),
+            Statement::Assert(_, condition, message) => {
+                self.infer(condition);
+                if let Some(message) = message {
+                    self.infer(message);
+                }
+            }
+            Statement::Test(_, body) => {
+                let variables = self.variables.clone();
+                let enums = self.enums.clone();
+                self.check(body);
+                self.variables = variables;
+                self.enums = enums;
+            }
+        }
+    }
+
+    fn declare_enum(&mut self, name: &Token, variants: &[EnumVariant]) {
+        let variants = variants
+            .iter()
+            .map(|v| (v.name.lexeme.clone(), v.fields.is_some()))
+            .collect();
+        self.enums.insert(name.lexeme.clone(), variants);
+        let binding = Binding {
+            declared: None,
+            current: Type::EnumObject(name.lexeme.clone()),
+        };
+        self.variables.insert(name.lexeme.clone(), binding);
+    }
+
+    fn resolve_annotation(&mut self, annotation: &TypeAnnotation) -> Option<Type> {
+        match annotation {
+            TypeAnnotation::Named(name) => match name.lexeme.as_str() {
+                "Any" => Some(Type::Any),
+                "Num" => Some(Type::Num),
+                "Str" => Some(Type::Str),
+                "Bool" => Some(Type::Bool),
+                "Range" => Some(Type::Range),
+                "Nil" | "nil" => Some(Type::Nil),
+                other if self.enums.contains_key(other) => Some(Type::Enum(other.to_string())),
+                other => {
+                    let error = TypeError::UnknownType(name.get_line(), other.to_string());
+                    self.errors.push(error.into());
+                    None
+                }
+            },
+            TypeAnnotation::Tuple(_, annotations) => {
+                let types: Option<Vec<Type>> = annotations.iter().map(|a| self.resolve_annotation(a)).collect();
+                types.map(Type::Tuple)
+            }
+        }
+    }
+
+    fn declare(&mut self, pattern: &Pattern, declared: Option<Type>, value: Type) {
+        match pattern {
+            Pattern::Name(name) => {
+                if let Some(declared) = &declared {
+                    self.check_annotation(name, declared, &value);
+                }
+                let current = match (&declared, value) {
+                    (Some(declared), Type::Any) => declared.clone(),
+                    (_, value) => value,
+                };
+                self.variables.insert(name.lexeme.clone(), Binding { declared, current });
+            }
+            Pattern::Tuple(paren, patterns) => {
+                let values = self.destructure(paren, patterns.len(), value);
+                let declared = match declared {
+                    Some(Type::Tuple(types)) if types.len() == patterns.len() => types.into_iter().map(Some).collect(),
+                    Some(Type::Any) | None => vec![None; patterns.len()],
+                    Some(other) => {
+                        let error = TypeError::NotDestructurable(paren.get_line(), other);
+                        self.errors.push(error.into());
+                        vec![None; patterns.len()]
+                    }
+                };
+                for ((pattern, declared), value) in patterns.iter().zip(declared).zip(values) {
+                    self.declare(pattern, declared, value);
+                }
+            }
+        }
+    }
+
+    fn assign(&mut self, pattern: &Pattern, value: Type) {
+        match pattern {
+            Pattern::Name(name) => {
+                let Some(binding) = self.variables.get(&name.lexeme) else {
+                    return;
+                };
+                if let Some(declared) = binding.declared.clone() {
+                    self.check_annotation(name, &declared, &value);
+                }
+                if let Some(binding) = self.variables.get_mut(&name.lexeme) {
+                    binding.current = match (&binding.declared, value) {
+                        (Some(declared), Type::Any) => declared.clone(),
+                        (_, value) => value,
+                    };
+                }
+            }
+            Pattern::Tuple(paren, patterns) => {
+                let values = self.destructure(paren, patterns.len(), value);
+                for (pattern, value) in patterns.iter().zip(values) {
+                    self.assign(pattern, value);
+                }
+            }
+        }
+    }
+
+    // splits the type of a value bound to a tuple pattern of `arity` names
+    fn destructure(&mut self, paren: &Token, arity: usize, value: Type) -> Vec<Type> {
+        match value {
+            Type::Tuple(types) if types.len() == arity => types,
+            Type::Tuple(types) => {
+                let error = TypeError::ArityMismatch(paren.get_line(), arity, types.len());
+                self.errors.push(error.into());
+                vec![Type::Any; arity]
+            }
+            Type::Any => vec![Type::Any; arity],
+            other => {
+                let error = TypeError::NotDestructurable(paren.get_line(), other);
+                self.errors.push(error.into());
+                vec![Type::Any; arity]
+            }
+        }
+    }
+
+    fn check_annotation(&mut self, name: &Token, declared: &Type, value: &Type) {
+        if !declared.accepts(value) {
+            let error = TypeError::AnnotationMismatch(name.get_line(), name.lexeme.clone(), declared.clone(), value.clone());
+            self.errors.push(error.into());
+        }
+    }
+
+    fn infer(&mut self, expr: &Expr) -> Type {
+        match expr {
+            Expr::Binary(b) => {
+                let left = self.infer(&b.left);
+                let right = self.infer(&b.right);
+                self.infer_binary(&b.operator, left, right)
+            }
+            Expr::Unary(u) => {
+                let next = self.infer(&u.next);
+                self.infer_unary(&u.operator, next)
+            }
+            Expr::Literal(l) => Type::of_literal(&l.value),
+            Expr::Grouping(g) => self.infer(&g.expression),
+            Expr::Var(v) => self
+                .variables
+                .get(&v.name.lexeme)
+                .map(|b| b.current.clone())
+                .unwrap_or(Type::Any),
+            Expr::Assign(a) => {
+                let value = self.infer(&a.value);
+                self.assign(&a.target, value.clone());
+                value
+            }
+            Expr::Tuple(t) => self.infer_elements(&t.elements).map(Type::Tuple).unwrap_or(Type::Any),
+            Expr::Call(c) => {
+                let callee = self.infer(&c.callee);
+                self.infer_elements(&c.arguments);
+                match callee {
+                    Type::Constructor(name) => Type::Enum(name),
+                    _ => Type::Any,
+                }
+            }
+            Expr::Get(g) => match self.infer(&g.object) {
+                Type::EnumObject(name) => {
+                    let variant = self
+                        .enums
+                        .get(&name)
+                        .and_then(|variants| variants.iter().find(|(v, _)| *v == g.name.lexeme));
+                    match variant {
+                        Some((_, true)) => Type::Constructor(name),
+                        Some((_, false)) => Type::Enum(name),
+                        None => Type::Any,
+                    }
+                }
+                _ => Type::Any,
+            },
+            Expr::Spread(s) => self.infer(&s.expression),
+            Expr::Index(i) => {
+                let object = self.infer(&i.object);
+                self.infer(&i.index);
+                match (object, &*i.index) {
+                    (Type::Tuple(types), Expr::Literal(l)) => match l.value {
+                        Literal::Number(n) if n >= 0.0 && n.fract() == 0.0 => {
+                            types.get(n as usize).cloned().unwrap_or(Type::Any)
+                        }
+                        _ => Type::Any,
+                    },
+                    _ => Type::Any,
+                }
+            }
+            Expr::OptionalChain(o) => {
+                self.infer(&o.expression);
+                Type::Any
+            }
+            Expr::Range(r) => {
+                let bounds = [Some(&r.start), Some(&r.end), r.step.as_ref()];
+                for bound in bounds.into_iter().flatten() {
+                    let ty = self.infer(bound);
+                    if !Type::Num.accepts(&ty) {
+                        let error = TypeError::InvalidRangeBound(r.operator.get_line(), ty);
+                        self.errors.push(error.into());
+                    }
+                }
+                Type::Range
+            }
+        }
+    }
+
+    // the types of tuple elements or call arguments with spreads flattened,
+    // None when a spread value's length is unknown
+    fn infer_elements(&mut self, elements: &[Expr]) -> Option<Vec<Type>> {
+        let mut types = Some(vec![]);
+        for element in elements {
+            let spread = match element {
+                Expr::Spread(s) => Some(&s.ellipsis),
+                _ => None,
+            };
+            let ty = self.infer(element);
+            match (spread, ty) {
+                (None, ty) => types.iter_mut().for_each(|t| t.push(ty.clone())),
+                (Some(_), Type::Tuple(spread)) => types.iter_mut().for_each(|t| t.extend(spread.clone())),
+                (Some(_), Type::Any | Type::Range) => types = None,
+                (Some(ellipsis), other) => {
+                    let error = TypeError::NotSpreadable(ellipsis.get_line(), other);
+                    self.errors.push(error.into());
+                    types = None;
+                }
+            }
+        }
+        types
+    }
+
+    // mirrors the operand combinations the interpreter accepts
+    fn infer_binary(&mut self, operator: &Token, left: Type, right: Type) -> Type {
+        let result = match operator.get_type() {
+            TokenType::Plus => match (&left, &right) {
+                (Type::Num, Type::Num) => Some(Type::Num),
+                (Type::Str, Type::Str) | (Type::Num, Type::Str) | (Type::Str, Type::Num) => Some(Type::Str),
+                (Type::Any, Type::Any | Type::Num | Type::Str) | (Type::Num | Type::Str, Type::Any) => Some(Type::Any),
+                _ => None,
+            },
+            TokenType::Minus | TokenType::Slash => match (&left, &right) {
+                (Type::Num | Type::Any, Type::Num | Type::Any) => Some(Type::Num),
+                _ => None,
+            },
+            TokenType::Star => match (&left, &right) {
+                (Type::Num, Type::Num) => Some(Type::Num),
+                (Type::Str, Type::Num) | (Type::Num, Type::Str) => Some(Type::Str),
+                (Type::Any, Type::Any | Type::Num | Type::Str) | (Type::Num | Type::Str, Type::Any) => Some(Type::Any),
+                _ => None,
+            },
+            TokenType::Less | TokenType::LessEqual | TokenType::Greater | TokenType::GreaterEqual => {
+                match (&left, &right) {
+                    (Type::Num | Type::Any, Type::Num | Type::Any) => Some(Type::Bool),
+                    _ => None,
+                }
+            }
+            TokenType::EqualEqual | TokenType::BangEqual => left.comparable(&right).then_some(Type::Bool),
+            TokenType::In | TokenType::NotIn => match (&left, &right) {
+                (_, Type::Tuple(_) | Type::Range | Type::Any) => Some(Type::Bool),
+                (Type::Str | Type::Any, Type::Str) => Some(Type::Bool),
+                _ => None,
+            },
+            TokenType::QuestionQuestion => match (&left, &right) {
+                (Type::Nil, right) => Some(right.clone()),
+                (Type::Any, _) => Some(Type::Any),
+                (left, _) => Some(left.clone()),
+            },
+            _ => Some(Type::Any),
+        };
+
+        result.unwrap_or_else(|| {
+            let error = TypeError::BinaryMismatch(operator.get_line(), operator.lexeme.clone(), left, right);
+            self.errors.push(error.into());
+            Type::Any
+        })
+    }
+
+    fn infer_unary(&mut self, operator: &Token, next: Type) -> Type {
+        let result = match (operator.get_type(), &next) {
+            (TokenType::Bang, Type::Bool | Type::Any) => Some(Type::Bool),
+            (TokenType::Minus, Type::Num | Type::Any) => Some(Type::Num),
+            _ => None,
+        };
+
+        result.unwrap_or_else(|| {
+            let error = TypeError::UnaryMismatch(operator.get_line(), operator.lexeme.clone(), next);
+            self.errors.push(error.into());
+            Type::Any
+        })
+    }
+}
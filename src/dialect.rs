@@ -0,0 +1,166 @@
+use crate::scanner::KEYWORDS;
+use crate::token::TokenType;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DialectError {
+    #[error("Unknown dialect '{0}', expected a preset ({1}) or a dialect file")]
+    UnknownDialect(String, String),
+
+    #[error("[{0}]: Expected 'spelling = keyword', got '{1}'")]
+    MalformedLine(usize, String),
+
+    #[error("[{0}]: '{1}' is not a keyword")]
+    UnknownKeyword(usize, String),
+
+    #[error("[{0}]: '{1}' is not a valid identifier")]
+    InvalidSpelling(usize, String),
+
+    #[error("[{0}]: '{1}' is used for more than one keyword")]
+    DuplicateSpelling(usize, String),
+
+    #[error("'{0}' is still the spelling of the '{0}' keyword, respell it as well")]
+    SpellingTaken(String),
+}
+
+// the built-in dialects, each line of a preset is written like a dialect file
+const PRESETS: &[(&str, &str)] = &[
+    ("standard", ""),
+    ("slang", "
+        bet = var
+        frfr = const
+        nocap = assert
+        yap = print
+        cook = fun
+        serve = return
+        vibecheck = if
+        sike = else
+        grind = while
+        based = true
+        cap = false
+        ghosted = nil
+        squad = class
+    "),
+];
+
+// how the keywords of the language are spelled, a dialect respells some of
+// the standard keywords and the rest keep their usual spelling, the standard
+// spelling of a respelled keyword becomes an ordinary identifier
+#[derive(Debug, Clone)]
+pub struct Dialect {
+    keywords: HashMap<String, TokenType>,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self::parse("").expect("the standard dialect is empty")
+    }
+}
+
+impl Dialect {
+    // a preset by name, anything else is read as the path of a dialect file
+    pub fn load(name: &str) -> Result<Self> {
+        Self::load_from(name, Path::new(""))
+    }
+
+    // the same as load, but a relative path is taken from `dir`, a pragma
+    // names its dialect file relative to the source file it is in
+    pub fn load_from(name: &str, dir: &Path) -> Result<Self> {
+        if let Some((_, source)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+            return Self::parse(source);
+        }
+        match std::fs::read_to_string(dir.join(name)) {
+            Ok(source) => Self::parse(&source),
+            Err(_) => {
+                let presets: Vec<&str> = PRESETS.iter().map(|(preset, _)| *preset).collect();
+                Err(DialectError::UnknownDialect(name.to_string(), presets.join(", ")).into())
+            }
+        }
+    }
+
+    // a dialect file has a `spelling = keyword` line for every keyword it
+    // respells, blank lines and lines starting with `#` are skipped
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut respelled: HashMap<TokenType, String> = HashMap::new();
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((spelling, keyword)) = line.split_once('=') else {
+                return Err(DialectError::MalformedLine(line_number, line.to_string()).into());
+            };
+            let (spelling, keyword) = (spelling.trim(), keyword.trim());
+            let Some(token_type) = KEYWORDS.get(keyword) else {
+                return Err(DialectError::UnknownKeyword(line_number, keyword.to_string()).into());
+            };
+            if !is_identifier(spelling) {
+                return Err(DialectError::InvalidSpelling(line_number, spelling.to_string()).into());
+            }
+            if respelled.values().any(|s| s == spelling) {
+                return Err(DialectError::DuplicateSpelling(line_number, spelling.to_string()).into());
+            }
+            respelled.insert(*token_type, spelling.to_string());
+        }
+
+        let mut keywords = HashMap::new();
+        for (standard, token_type) in KEYWORDS.entries() {
+            let spelling = respelled.get(token_type).map_or(*standard, String::as_str);
+            // a respelling can only reuse the standard spelling of a keyword
+            // that is itself respelled
+            if keywords.insert(spelling.to_string(), *token_type).is_some() {
+                return Err(DialectError::SpellingTaken(spelling.to_string()).into());
+            }
+        }
+        Ok(Self { keywords })
+    }
+
+    pub fn keyword(&self, text: &str) -> Option<TokenType> {
+        self.keywords.get(text).copied()
+    }
+
+    // how this dialect spells a keyword
+    pub fn spelling(&self, token_type: TokenType) -> Option<&str> {
+        self.keywords
+            .iter()
+            .find(|(_, t)| **t == token_type)
+            .map(|(spelling, _)| spelling.as_str())
+    }
+}
+
+// the dialect named by a `// dialect: name` comment, which has to come before
+// any code in the file
+pub fn pragma(source: &str) -> Option<&str> {
+    pragma_span(source).map(|span| &source[span])
+}
+
+// the byte range of the name in the dialect pragma
+pub fn pragma_span(source: &str) -> Option<Range<usize>> {
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let comment = trimmed.strip_prefix("//")?;
+        if let Some(name) = comment.trim().strip_prefix("dialect:") {
+            let name = name.trim();
+            let name_start = start + line.rfind(name)?;
+            return Some(name_start..name_start + name.len());
+        }
+    }
+    None
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,178 @@
+use crate::statements::Statement;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+// the documentation of one file, its `//!` comment and its top level
+// declarations in source order
+pub struct ModuleDoc {
+    pub name: String,
+    pub doc: Option<String>,
+    pub items: Vec<Item>,
+}
+
+pub struct Item {
+    pub kind: ItemKind,
+    // `name` or `name: Type`, tuple patterns are kept whole
+    pub signature: String,
+    pub doc: Option<String>,
+    // the variants of an enum, `Name` or `Name(field, ...)`, with their docs
+    pub members: Vec<(String, Option<String>)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ItemKind {
+    Const,
+    Var,
+    Enum,
+}
+
+impl ItemKind {
+    const ALL: [ItemKind; 3] = [ItemKind::Const, ItemKind::Var, ItemKind::Enum];
+
+    fn heading(&self) -> &'static str {
+        match self {
+            ItemKind::Const => "Constants",
+            ItemKind::Var => "Variables",
+            ItemKind::Enum => "Enums",
+        }
+    }
+}
+
+impl ModuleDoc {
+    pub fn new(name: &str, doc: Option<&str>, statements: &[Statement]) -> Self {
+        let mut items = vec![];
+        for statement in statements {
+            let item = match statement {
+                Statement::Var(pattern, annotation, _, doc) | Statement::Const(pattern, annotation, _, doc) => Item {
+                    kind: if matches!(statement, Statement::Const(..)) { ItemKind::Const } else { ItemKind::Var },
+                    signature: match annotation {
+                        Some(annotation) => format!("{pattern}: {annotation}"),
+                        None => pattern.to_string(),
+                    },
+                    doc: doc.clone(),
+                    members: vec![],
+                },
+                Statement::Enum(name, variants, doc) => Item {
+                    kind: ItemKind::Enum,
+                    signature: format!("enum {name}"),
+                    doc: doc.clone(),
+                    members: variants
+                        .iter()
+                        .map(|variant| {
+                            let signature = match &variant.fields {
+                                Some(fields) => {
+                                    let fields: Vec<&str> = fields.iter().map(|f| f.lexeme.as_str()).collect();
+                                    format!("{}({})", variant.name, fields.join(", "))
+                                }
+                                None => variant.name.to_string(),
+                            };
+                            (signature, variant.doc.clone())
+                        })
+                        .collect(),
+                },
+                _ => continue,
+            };
+            items.push(item);
+        }
+        Self {
+            name: name.to_string(),
+            doc: doc.map(str::to_string),
+            items,
+        }
+    }
+}
+
+pub fn render(modules: &[ModuleDoc], format: DocFormat) -> String {
+    match format {
+        DocFormat::Markdown => modules.iter().map(markdown).collect::<Vec<_>>().join("\n"),
+        DocFormat::Html => html(modules),
+    }
+}
+
+// doc comments are written in markdown, so they are copied as they are
+fn markdown(module: &ModuleDoc) -> String {
+    let mut out = format!("# {}\n\n", module.name);
+    if let Some(doc) = &module.doc {
+        out.push_str(&format!("{doc}\n\n"));
+    }
+    for kind in ItemKind::ALL {
+        let items: Vec<&Item> = module.items.iter().filter(|item| item.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("## {}\n\n", kind.heading()));
+        for item in items {
+            out.push_str(&format!("### `{}`\n\n", item.signature));
+            if let Some(doc) = &item.doc {
+                out.push_str(&format!("{doc}\n\n"));
+            }
+            for (signature, doc) in &item.members {
+                match doc {
+                    Some(doc) => out.push_str(&format!("- `{signature}`: {}\n", doc.replace('\n', " "))),
+                    None => out.push_str(&format!("- `{signature}`\n")),
+                }
+            }
+            if !item.members.is_empty() {
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+// one page for every module, doc comments become paragraphs of escaped text
+fn html(modules: &[ModuleDoc]) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>API documentation</title>\n</head>\n<body>\n");
+    for module in modules {
+        out.push_str(&format!("<section>\n<h1>{}</h1>\n", escape(&module.name)));
+        if let Some(doc) = &module.doc {
+            out.push_str(&paragraphs(doc));
+        }
+        for kind in ItemKind::ALL {
+            let items: Vec<&Item> = module.items.iter().filter(|item| item.kind == kind).collect();
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("<h2>{}</h2>\n", kind.heading()));
+            for item in items {
+                out.push_str(&format!("<h3><code>{}</code></h3>\n", escape(&item.signature)));
+                if let Some(doc) = &item.doc {
+                    out.push_str(&paragraphs(doc));
+                }
+                if item.members.is_empty() {
+                    continue;
+                }
+                out.push_str("<ul>\n");
+                for (signature, doc) in &item.members {
+                    out.push_str(&format!("<li><code>{}</code>", escape(signature)));
+                    if let Some(doc) = doc {
+                        out.push_str(&format!(": {}", escape(doc)));
+                    }
+                    out.push_str("</li>\n");
+                }
+                out.push_str("</ul>\n");
+            }
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn paragraphs(doc: &str) -> String {
+    doc.split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("<p>{}</p>\n", escape(p.trim())))
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
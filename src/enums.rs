@@ -0,0 +1,48 @@
+use crate::token::Literal;
+
+// the runtime description of an `enum` declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<VariantDef>,
+}
+
+// `fields` is None for unit variants like `Empty`, which are values on their
+// own instead of constructors
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantDef {
+    pub name: String,
+    pub fields: Option<Vec<String>>,
+}
+
+impl EnumDef {
+    pub fn variant(&self, name: &str) -> Option<usize> {
+        self.variants.iter().position(|v| v.name == name)
+    }
+}
+
+// a constructed value of one of the variants, fields are stored in declaration
+// order next to their names
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+    pub enum_name: String,
+    pub variant: String,
+    pub fields: Vec<(String, Literal)>,
+}
+
+impl EnumValue {
+    pub fn field(&self, name: &str) -> Option<&Literal> {
+        self.fields.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+}
+
+impl std::fmt::Display for EnumValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.enum_name, self.variant)?;
+        if !self.fields.is_empty() {
+            let fields: Vec<String> = self.fields.iter().map(|(n, v)| format!("{n}: {v}")).collect();
+            write!(f, "({})", fields.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::interpreter::InterpretError;
+use crate::token::{Literal, Token};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+// variable bindings, currently there is only the global scope
+#[derive(Default)]
+pub struct Environment {
+    values: HashMap<String, Literal>,
+    constants: HashSet<String>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            constants: HashSet::new(),
+        }
+    }
+
+    pub fn define(&mut self, name: &Token, value: Literal) -> Result<()> {
+        if self.constants.contains(&name.lexeme) {
+            return Err(InterpretError::RedefineConstant(name.get_line(), name.lexeme.clone()).into());
+        }
+        self.values.insert(name.lexeme.clone(), value);
+        Ok(())
+    }
+
+    pub fn define_constant(&mut self, name: &Token, value: Literal) -> Result<()> {
+        self.define(name, value)?;
+        self.constants.insert(name.lexeme.clone());
+        Ok(())
+    }
+
+    // globals provided by the interpreter itself, they are not constant so
+    // that scripts which already use names like `time` can still declare them
+    pub fn define_builtin(&mut self, name: &str, value: Literal) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Literal> {
+        match self.values.get(&name.lexeme) {
+            Some(value) => Ok(value.clone()),
+            None => Err(InterpretError::UndefinedVariable(name.get_line(), name.lexeme.clone()).into()),
+        }
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Literal) -> Result<()> {
+        if self.constants.contains(&name.lexeme) {
+            return Err(InterpretError::AssignToConstant(name.get_line(), name.lexeme.clone()).into());
+        }
+        match self.values.get_mut(&name.lexeme) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(InterpretError::UndefinedVariable(name.get_line(), name.lexeme.clone()).into()),
+        }
+    }
+
+    pub fn get_constants(&self) -> &HashSet<String> {
+        &self.constants
+    }
+}
@@ -1,32 +1,117 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 use crate::ast::Expr;
-use crate::ast::{Binary, Grouping, LiteralExpr, Unary};
-use crate::token::Literal;
+use crate::ast::{Assign, Binary, Call, Get, Grouping, Index, LiteralExpr, OptionalChain, Pattern, RangeExpr, Spread, Tuple, Unary, Variable};
+use crate::enums::{EnumDef, EnumValue, VariantDef};
+use crate::environment::Environment;
+use crate::range::{RangeValue, MAX_EXPANDED};
+use crate::stdlib::random::Rng;
+use crate::stdlib::time::{self, Clock, SystemClock};
+use crate::stdlib::{self, regex, strings, sys};
+use crate::statements::EnumVariant;
+use crate::token::{Literal, Token};
 use crate::token::TokenType;
 use crate::statements::Statement;
 use anyhow::Result;
 use anyhow::Error;
+use std::rc::Rc;
 
 #[derive(thiserror::Error, Debug)]
-enum InterpretError {
+pub enum InterpretError {
     #[error("Error, incorrect type passed to expression")]
     IncorrectType,
+    #[error("[{0}]: Undefined variable '{1}'")]
+    UndefinedVariable(usize, String),
+    #[error("[{0}]: Cannot destructure {1}, expected a tuple")]
+    NotDestructurable(usize, String),
+    #[error("[{0}]: Pattern expects {1} values but the tuple has {2}")]
+    ArityMismatch(usize, usize, usize),
+    #[error("[{0}]: {1} expects {2} arguments but got {3}")]
+    WrongArgumentCount(usize, String, String, usize),
+    #[error("[{0}]: Can only call functions and enum variant constructors, got {1}")]
+    NotCallable(usize, String),
+    #[error("[{0}]: {1}: {2}")]
+    InvalidArgument(usize, String, String),
+    #[error("[{0}]: {1} is undefined for {2}")]
+    DomainError(usize, String, String),
+    #[error("[{0}]: {1} failed for '{2}': {3}")]
+    Io(usize, String, String, String),
+    #[error("[{0}]: Undefined property '{1}' on {2}")]
+    UndefinedProperty(usize, String, String),
+    #[error("[{0}]: Cannot assign to constant '{1}'")]
+    AssignToConstant(usize, String),
+    #[error("[{0}]: Cannot redefine constant '{1}'")]
+    RedefineConstant(usize, String),
+    #[error("[{0}]: Cannot spread {1}, expected a tuple, range or map")]
+    NotSpreadable(usize, String),
+    #[error("[{0}]: Spread is only allowed in call arguments and tuples")]
+    MisplacedSpread(usize),
+    #[error("[{0}]: Cannot index into {1}")]
+    NotIndexable(usize, String),
+    #[error("[{0}]: Index must be a non-negative whole number, got {1}")]
+    InvalidIndex(usize, String),
+    #[error("[{0}]: Index {1} is out of range")]
+    IndexOutOfRange(usize, String),
+    #[error("[{0}]: Key {1} is not in the map")]
+    MissingKey(usize, String),
+    #[error("[{0}]: Range bounds and step must be finite numbers, got {1}")]
+    InvalidRangeBound(usize, String),
+    #[error("[{0}]: Range {1} is too large to expand, it has more than {2} elements")]
+    RangeTooLarge(usize, String, usize),
+    #[error("[{0}]: Range step cannot be 0")]
+    ZeroStep(usize),
+    #[error("[{0}]: Cannot test whether {1} is in {2}")]
+    NotAContainer(usize, String, String),
+    #[error("[{0}]: Assertion failed")]
+    AssertionFailed(usize),
+    #[error("[{0}]: Assertion failed: {1}")]
+    AssertionFailedWith(usize, String),
+    #[error("[{0}]: Assert condition must be a boolean, got {1}")]
+    NotACondition(usize, String),
+    // raised by sys.exit to unwind out of the script
+    #[error("[{0}]: Exited with code {1}")]
+    Exit(usize, i32),
+}
+
+// how the names of a pattern are bound by `bind`
+#[derive(Clone, Copy)]
+enum Binding {
+    Var,
+    Const,
+    Assign,
 }
 
 // handles the interpretation of Expr's, it does not hold the expression state
-// only the error state
+// only the error state and the variable bindings
 pub struct Interpreter {
-    pub errors: Vec<Error>
+    pub errors: Vec<Error>,
+    pub environment: Environment,
+    // the generator behind the random module
+    pub random: Rng,
+    // where the time module reads the time from
+    pub clock: Box<dyn Clock>,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
+        let mut environment = Environment::new();
+        for (name, value) in stdlib::globals() {
+            environment.define_builtin(name, value);
+        }
+
         Self {
-            errors: vec![]
+            errors: vec![],
+            environment,
+            random: Rng::from_time(),
+            clock: Box::new(SystemClock::new()),
         }
     }
 
+    // makes the arguments after the script path available as sys.args
+    pub fn set_args(&mut self, args: &[String]) {
+        self.environment.define_builtin("sys", Literal::Module(Rc::new(sys::module(args))));
+    }
+
     pub fn interpret(&mut self, statements: Vec<Statement>) -> Result<()> {
         for statement in statements {
             self.execute(statement)?;
@@ -47,18 +132,92 @@ impl Interpreter {
             Statement::Expression(e) => {
                 let literal = self.evaluate(e)?;
             },
-            Statement::Var(n, e) => (),
+            Statement::Var(pattern, _, e, _) => {
+                let value = self.evaluate(e)?;
+                self.bind(&pattern, value, Binding::Var)?;
+            },
+            Statement::Const(pattern, _, e, _) => {
+                let value = self.evaluate(e)?;
+                self.bind(&pattern, value, Binding::Const)?;
+            },
+            Statement::Enum(name, variants, _) => self.declare_enum(name, variants)?,
+            Statement::Assert(keyword, condition, message) => self.assert(keyword, condition, message)?,
+            Statement::Test(..) => (),
         }
         Ok(())
     }
 
+    // the message is only evaluated when the assertion fails
+    fn assert(&mut self, keyword: Token, condition: Expr, message: Option<Expr>) -> Result<()> {
+        let line = keyword.get_line();
+        match self.evaluate(condition)? {
+            Literal::Boolean(true) => Ok(()),
+            Literal::Boolean(false) => match message {
+                Some(message) => {
+                    let message = self.evaluate(message)?.to_string();
+                    Err(InterpretError::AssertionFailedWith(line, message).into())
+                }
+                None => Err(InterpretError::AssertionFailed(line).into()),
+            },
+            other => Err(InterpretError::NotACondition(line, other.to_string()).into()),
+        }
+    }
+
+    fn declare_enum(&mut self, name: Token, variants: Vec<EnumVariant>) -> Result<()> {
+        let variants = variants
+            .into_iter()
+            .map(|v| VariantDef {
+                name: v.name.lexeme,
+                fields: v.fields.map(|fields| fields.into_iter().map(|f| f.lexeme).collect()),
+            })
+            .collect();
+        let def = EnumDef {
+            name: name.lexeme.clone(),
+            variants,
+        };
+        self.environment.define(&name, Literal::Enum(Rc::new(def)))
+    }
+
+    // binds a value to every name in the pattern, either declaring the names
+    // or assigning to existing bindings
+    fn bind(&mut self, pattern: &Pattern, value: Literal, binding: Binding) -> Result<()> {
+        match pattern {
+            Pattern::Name(name) => match binding {
+                Binding::Var => self.environment.define(name, value),
+                Binding::Const => self.environment.define_constant(name, value),
+                Binding::Assign => self.environment.assign(name, value),
+            },
+            Pattern::Tuple(paren, patterns) => {
+                let values = match value {
+                    Literal::Tuple(values) => values,
+                    other => return Err(InterpretError::NotDestructurable(paren.get_line(), other.to_string()).into()),
+                };
+                if values.len() != patterns.len() {
+                    return Err(InterpretError::ArityMismatch(paren.get_line(), patterns.len(), values.len()).into());
+                }
+                for (pattern, value) in patterns.iter().zip(values) {
+                    self.bind(pattern, value, binding)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     fn evaluate(&mut self, expr: Expr) -> Result<Literal> {
         match expr {
             Expr::Binary(b) => Ok(self.interpret_binary(*b)?),
             Expr::Unary(u) => Ok(self.interpret_unary(*u)?),
             Expr::Literal(l) => Ok(self.interpret_literal(l)),
-            Expr::Grouping(g) => Ok(self.interpret_grouping(*g)),
-            Expr::Var(v) => todo!(),
+            Expr::Grouping(g) => self.interpret_grouping(*g),
+            Expr::Var(v) => self.interpret_variable(*v),
+            Expr::Assign(a) => self.interpret_assign(*a),
+            Expr::Tuple(t) => self.interpret_tuple(*t),
+            Expr::Call(c) => Ok(self.interpret_call(*c)?.unwrap_or(Literal::Nil)),
+            Expr::Get(g) => Ok(self.interpret_get(*g)?.unwrap_or(Literal::Nil)),
+            Expr::Index(i) => Ok(self.interpret_index(*i)?.unwrap_or(Literal::Nil)),
+            Expr::OptionalChain(o) => self.interpret_optional_chain(*o),
+            Expr::Range(r) => self.interpret_range(*r),
+            Expr::Spread(s) => Err(InterpretError::MisplacedSpread(s.ellipsis.get_line()).into()),
         }
     }
 
@@ -75,12 +234,19 @@ impl Interpreter {
 
             TokenType::Less => self.less(left_expr, right_expr),            
             TokenType::LessEqual => self.less_equal(left_expr, right_expr),            
+            TokenType::Greater => self.greater(left_expr, right_expr),
+            TokenType::GreaterEqual => self.greater_equal(left_expr, right_expr),
 
             TokenType::Plus => self.plus(left_expr, right_expr),            
             TokenType::Minus => self.minus(left_expr, right_expr),            
 
             TokenType::Star => self.mult(left_expr, right_expr),            
             TokenType::Slash => self.div(left_expr, right_expr),            
+
+            TokenType::QuestionQuestion => self.coalesce(left_expr, right_expr),
+
+            TokenType::In => Ok(Literal::Boolean(self.contains(left_expr, right_expr, expr.operator.get_line())?)),
+            TokenType::NotIn => Ok(Literal::Boolean(!self.contains(left_expr, right_expr, expr.operator.get_line())?)),
             _ => Err(InterpretError::IncorrectType.into())
         }
     }
@@ -89,7 +255,17 @@ impl Interpreter {
         match (left, right) {
             (Literal::Number(_), Literal::Number(_)) |
             (Literal::String(_), Literal::String(_)) |
-            (Literal::Boolean(_), Literal::Boolean(_)) => true,
+            (Literal::Boolean(_), Literal::Boolean(_)) |
+            (Literal::Tuple(_), Literal::Tuple(_)) |
+            (Literal::Variant(_), Literal::Variant(_)) |
+            (Literal::Enum(_), Literal::Enum(_)) |
+            (Literal::Constructor(..), Literal::Constructor(..)) |
+            (Literal::Range(_), Literal::Range(_)) |
+            (Literal::Native(_), Literal::Native(_)) |
+            (Literal::Module(_), Literal::Module(_)) |
+            (Literal::Date(_), Literal::Date(_)) |
+            (Literal::Map(_), Literal::Map(_)) |
+            (Literal::Regex(_), Literal::Regex(_)) => true,
             (Literal::Nil, Literal::Nil) => true,
             (Literal::Nil, _) |
             (_, Literal::Nil) => false,
@@ -110,6 +286,14 @@ impl Interpreter {
         }
     }
 
+    // the right side is only evaluated when the left side is nil
+    fn coalesce(&mut self, left: Expr, right: Expr) -> Result<Literal> {
+        match self.interpret_expression(left)? {
+            Literal::Nil => self.interpret_expression(right),
+            value => Ok(value),
+        }
+    }
+
     fn minus(&mut self, left: Expr, right: Expr) -> Result<Literal> {
         let left = self.interpret_expression(left)?;
         let right = self.interpret_expression(right)?;
@@ -147,6 +331,7 @@ impl Interpreter {
         let right = self.interpret_expression(right)?;
         match (left, right) {
             (Literal::Number(a), Literal::Number(b)) => Ok(Literal::Boolean(a < b)),
+            (Literal::Date(a), Literal::Date(b)) => Ok(Literal::Boolean(a < b)),
             _ => Err(InterpretError::IncorrectType.into())
         }
 
@@ -157,12 +342,33 @@ impl Interpreter {
         let right = self.interpret_expression(right)?;
         match (left, right) {
             (Literal::Number(a), Literal::Number(b)) => Ok(Literal::Boolean(a <= b)),
+            (Literal::Date(a), Literal::Date(b)) => Ok(Literal::Boolean(a <= b)),
             _ => Err(InterpretError::IncorrectType.into())
         }
 
 
     }
 
+    fn greater(&mut self, left: Expr, right: Expr) -> Result<Literal> {
+        let left = self.interpret_expression(left)?;
+        let right = self.interpret_expression(right)?;
+        match (left, right) {
+            (Literal::Number(a), Literal::Number(b)) => Ok(Literal::Boolean(a > b)),
+            (Literal::Date(a), Literal::Date(b)) => Ok(Literal::Boolean(a > b)),
+            _ => Err(InterpretError::IncorrectType.into())
+        }
+    }
+
+    fn greater_equal(&mut self, left: Expr, right: Expr) -> Result<Literal> {
+        let left = self.interpret_expression(left)?;
+        let right = self.interpret_expression(right)?;
+        match (left, right) {
+            (Literal::Number(a), Literal::Number(b)) => Ok(Literal::Boolean(a >= b)),
+            (Literal::Date(a), Literal::Date(b)) => Ok(Literal::Boolean(a >= b)),
+            _ => Err(InterpretError::IncorrectType.into())
+        }
+    }
+
     fn not_equal(&mut self, left: Expr, right: Expr) -> Result<Literal> {
         let left = self.interpret_expression(left)?;
         let right = self.interpret_expression(right)?;
@@ -174,6 +380,16 @@ impl Interpreter {
                 Literal::Number(_) => Ok(Literal::Boolean(result)),
                 Literal::Boolean(_) => Ok(Literal::Boolean(result)),
                 Literal::String(_) => Ok(Literal::Boolean(result)),
+                Literal::Tuple(_) => Ok(Literal::Boolean(result)),
+                Literal::Enum(_) |
+                Literal::Constructor(..) |
+                Literal::Variant(_) |
+                Literal::Range(_) |
+                Literal::Native(_) |
+                Literal::Module(_) |
+                Literal::Date(_) |
+                Literal::Map(_) |
+                Literal::Regex(_) => Ok(Literal::Boolean(result)),
                 Literal::Nil => Ok(Literal::Boolean(result)),
             }
         };
@@ -191,12 +407,38 @@ impl Interpreter {
                 Literal::Number(_) => Ok(Literal::Boolean(result)),
                 Literal::Boolean(_) => Ok(Literal::Boolean(result)),
                 Literal::String(_) => Ok(Literal::Boolean(result)),
-                Literal::Nil => unreachable!(),
+                Literal::Tuple(_) => Ok(Literal::Boolean(result)),
+                Literal::Enum(_) |
+                Literal::Constructor(..) |
+                Literal::Variant(_) |
+                Literal::Range(_) |
+                Literal::Native(_) |
+                Literal::Module(_) |
+                Literal::Date(_) |
+                Literal::Map(_) |
+                Literal::Regex(_) => Ok(Literal::Boolean(result)),
+                Literal::Nil => Ok(Literal::Boolean(result)),
             }
         };
         Err(InterpretError::IncorrectType.into())
     }
 
+    // `needle in haystack`, tuples compare their elements, strings look for a
+    // substring and ranges check whether the number is one of their steps
+    fn contains(&mut self, needle: Expr, haystack: Expr, line: usize) -> Result<bool> {
+        let needle = self.interpret_expression(needle)?;
+        let haystack = self.interpret_expression(haystack)?;
+        match (&needle, &haystack) {
+            (needle, Literal::Tuple(values)) => Ok(values.contains(needle)),
+            (Literal::String(needle), Literal::String(haystack)) => Ok(haystack.contains(needle.as_str())),
+            (Literal::Number(n), Literal::Range(range)) => Ok(range.contains(*n)),
+            (_, Literal::Range(_)) => Ok(false),
+            (Literal::String(key), Literal::Map(map)) => Ok(map.get(key).is_some()),
+            (_, Literal::Map(_)) => Ok(false),
+            _ => Err(InterpretError::NotAContainer(line, needle.to_string(), haystack.to_string()).into()),
+        }
+    }
+
     //----------------------------UNARY EXPRESSIONS----------------------------
     fn interpret_unary(&mut self, expr: Unary) -> Result<Literal> {
         let token_type = expr.operator.get_type();
@@ -229,7 +471,245 @@ impl Interpreter {
         expr.value
     }
     //---------------------------GROUPING EXPRESSIONS--------------------------
-    fn interpret_grouping(&mut self, expr: Grouping) -> Literal {
-        self.interpret_expression(*expr.expression).unwrap()
+    fn interpret_grouping(&mut self, expr: Grouping) -> Result<Literal> {
+        self.interpret_expression(*expr.expression)
+    }
+    //---------------------------VARIABLE EXPRESSIONS--------------------------
+    fn interpret_variable(&mut self, expr: Variable) -> Result<Literal> {
+        self.environment.get(&expr.name)
+    }
+
+    fn interpret_assign(&mut self, expr: Assign) -> Result<Literal> {
+        // the whole right hand side is evaluated before anything is bound so
+        // that `(a, b) = (b, a);` swaps
+        let value = self.interpret_expression(*expr.value)?;
+        self.bind(&expr.target, value.clone(), Binding::Assign)?;
+        Ok(value)
+    }
+    //----------------------------TUPLE EXPRESSIONS----------------------------
+    fn interpret_tuple(&mut self, expr: Tuple) -> Result<Literal> {
+        Ok(Literal::Tuple(self.interpret_elements(expr.elements)?))
+    }
+
+    // evaluates tuple elements or call arguments, splicing in spread values
+    fn interpret_elements(&mut self, elements: Vec<Expr>) -> Result<Vec<Literal>> {
+        let mut values = vec![];
+        for element in elements {
+            match element {
+                Expr::Spread(spread) => values.extend(self.interpret_spread(*spread)?),
+                element => values.push(self.interpret_expression(element)?),
+            }
+        }
+        Ok(values)
+    }
+
+    fn interpret_spread(&mut self, expr: Spread) -> Result<Vec<Literal>> {
+        let value = self.interpret_expression(*expr.expression)?;
+        match Self::sequence(&value, expr.ellipsis.get_line())? {
+            Some(values) => Ok(values),
+            None => Err(InterpretError::NotSpreadable(expr.ellipsis.get_line(), value.to_string()).into()),
+        }
+    }
+
+    // the values a tuple, range or map spreads into, a map spreads into
+    // (key, value) pairs
+    pub fn sequence(value: &Literal, line: usize) -> Result<Option<Vec<Literal>>> {
+        Ok(match value {
+            Literal::Tuple(values) => Some(values.clone()),
+            Literal::Range(range) if range.len() > MAX_EXPANDED => {
+                return Err(InterpretError::RangeTooLarge(line, range.to_string(), MAX_EXPANDED).into());
+            }
+            Literal::Range(range) => Some(range.iter().map(Literal::Number).collect()),
+            Literal::Map(map) => Some(map
+                .entries()
+                .iter()
+                .map(|(k, v)| Literal::Tuple(vec![Literal::String(k.clone()), v.clone()]))
+                .collect()),
+            _ => None,
+        })
+    }
+    //----------------------------RANGE EXPRESSIONS----------------------------
+    fn interpret_range(&mut self, expr: RangeExpr) -> Result<Literal> {
+        let line = expr.operator.get_line();
+        let start = self.interpret_range_bound(*expr.start, line)?;
+        let end = self.interpret_range_bound(*expr.end, line)?;
+        let step = match expr.step {
+            Some(step) => self.interpret_range_bound(*step, line)?,
+            None => 1.0,
+        };
+        if step == 0.0 {
+            return Err(InterpretError::ZeroStep(line).into());
+        }
+
+        Ok(Literal::Range(RangeValue {
+            start,
+            end,
+            step,
+            inclusive: *expr.operator.get_type() == TokenType::DotDotEqual,
+        }))
+    }
+
+    fn interpret_range_bound(&mut self, expr: Expr, line: usize) -> Result<f32> {
+        match self.interpret_expression(expr)? {
+            Literal::Number(n) if n.is_finite() => Ok(n),
+            other => Err(InterpretError::InvalidRangeBound(line, other.to_string()).into()),
+        }
+    }
+    //-----------------------------CALL EXPRESSIONS----------------------------
+    // calls, property accesses and indexing return None when an optional
+    // `?.` or `?[` inside the same chain met nil, which skips the rest of it
+    fn interpret_link(&mut self, expr: Expr) -> Result<Option<Literal>> {
+        match expr {
+            Expr::Call(c) => self.interpret_call(*c),
+            Expr::Get(g) => self.interpret_get(*g),
+            Expr::Index(i) => self.interpret_index(*i),
+            other => Ok(Some(self.evaluate(other)?)),
+        }
+    }
+
+    fn interpret_optional_chain(&mut self, expr: OptionalChain) -> Result<Literal> {
+        Ok(self.interpret_link(*expr.expression)?.unwrap_or(Literal::Nil))
+    }
+
+    fn interpret_call(&mut self, expr: Call) -> Result<Option<Literal>> {
+        let Some(callee) = self.interpret_link(*expr.callee)? else {
+            return Ok(None);
+        };
+        let arguments = self.interpret_elements(expr.arguments)?;
+        Ok(Some(self.call_value(callee, arguments, expr.paren.get_line())?))
+    }
+
+    // calls an enum variant constructor or a native, also used by natives that
+    // take a callback
+    pub fn call_value(&mut self, callee: Literal, arguments: Vec<Literal>, line: usize) -> Result<Literal> {
+        match callee {
+            Literal::Constructor(def, index) => {
+                let variant = &def.variants[index];
+                let fields = variant.fields.as_deref().unwrap_or_default();
+                if fields.len() != arguments.len() {
+                    let name = format!("{}.{}", def.name, variant.name);
+                    return Err(InterpretError::WrongArgumentCount(line, name, fields.len().to_string(), arguments.len()).into());
+                }
+                Ok(Literal::Variant(Box::new(EnumValue {
+                    enum_name: def.name.clone(),
+                    variant: variant.name.clone(),
+                    fields: fields.iter().cloned().zip(arguments).collect(),
+                })))
+            }
+            Literal::Native(native) => native.call(self, arguments, line),
+            other => Err(InterpretError::NotCallable(line, other.to_string()).into()),
+        }
+    }
+    //------------------------------GET EXPRESSIONS----------------------------
+    fn interpret_get(&mut self, expr: Get) -> Result<Option<Literal>> {
+        let Some(object) = self.interpret_link(*expr.object)? else {
+            return Ok(None);
+        };
+        if expr.optional && object == Literal::Nil {
+            return Ok(None);
+        }
+        let name = &expr.name;
+
+        let property = match &object {
+            Literal::Enum(def) => def.variant(&name.lexeme).map(|index| {
+                let variant = &def.variants[index];
+                match variant.fields {
+                    Some(_) => Literal::Constructor(def.clone(), index),
+                    None => Literal::Variant(Box::new(EnumValue {
+                        enum_name: def.name.clone(),
+                        variant: variant.name.clone(),
+                        fields: vec![],
+                    })),
+                }
+            }),
+            Literal::Variant(value) => value.field(&name.lexeme).cloned(),
+            Literal::String(s) => strings::method(s, &name.lexeme).map(Literal::Native),
+            Literal::Module(module) => module.get(&name.lexeme).cloned(),
+            Literal::Date(date) => time::member(date, &name.lexeme),
+            Literal::Map(map) => map.get(&name.lexeme).cloned(),
+            Literal::Regex(re) => regex::method(re, &name.lexeme).map(Literal::Native),
+            _ => None,
+        };
+
+        match property {
+            Some(property) => Ok(Some(property)),
+            None => Err(InterpretError::UndefinedProperty(name.get_line(), name.lexeme.clone(), object.to_string()).into()),
+        }
+    }
+    //-----------------------------INDEX EXPRESSIONS---------------------------
+    fn interpret_index(&mut self, expr: Index) -> Result<Option<Literal>> {
+        let Some(object) = self.interpret_link(*expr.object)? else {
+            return Ok(None);
+        };
+        if expr.optional && object == Literal::Nil {
+            return Ok(None);
+        }
+        let index = self.interpret_expression(*expr.index)?;
+        let line = expr.bracket.get_line();
+
+        match (object, index) {
+            (Literal::Tuple(values), Literal::Range(range)) => {
+                let mut slice = vec![];
+                for position in range.iter() {
+                    let position = Self::as_position(&Literal::Number(position), line)?;
+                    match values.get(position) {
+                        Some(value) => slice.push(value.clone()),
+                        None => return Err(InterpretError::IndexOutOfRange(line, position.to_string()).into()),
+                    }
+                }
+                Ok(Some(Literal::Tuple(slice)))
+            }
+            (Literal::Tuple(values), index) => {
+                let position = Self::as_position(&index, line)?;
+                match values.into_iter().nth(position) {
+                    Some(value) => Ok(Some(value)),
+                    None => Err(InterpretError::IndexOutOfRange(line, index.to_string()).into()),
+                }
+            }
+            (Literal::String(s), Literal::Range(range)) => {
+                let chars: Vec<char> = s.chars().collect();
+                let mut slice = String::new();
+                for position in range.iter() {
+                    let position = Self::as_position(&Literal::Number(position), line)?;
+                    match chars.get(position) {
+                        Some(c) => slice.push(*c),
+                        None => return Err(InterpretError::IndexOutOfRange(line, position.to_string()).into()),
+                    }
+                }
+                Ok(Some(Literal::String(slice)))
+            }
+            (Literal::String(s), index) => {
+                let position = Self::as_position(&index, line)?;
+                match s.chars().nth(position) {
+                    Some(c) => Ok(Some(Literal::String(c.to_string()))),
+                    None => Err(InterpretError::IndexOutOfRange(line, index.to_string()).into()),
+                }
+            }
+            (Literal::Range(range), index) => {
+                let position = Self::as_position(&index, line)?;
+                match range.get(position) {
+                    Some(value) => Ok(Some(Literal::Number(value))),
+                    None => Err(InterpretError::IndexOutOfRange(line, index.to_string()).into()),
+                }
+            }
+            (Literal::Map(map), key) => {
+                let value = match &key {
+                    Literal::String(key) => map.get(key).cloned(),
+                    _ => None,
+                };
+                match value {
+                    Some(value) => Ok(Some(value)),
+                    None => Err(InterpretError::MissingKey(line, key.to_string()).into()),
+                }
+            }
+            (other, _) => Err(InterpretError::NotIndexable(line, other.to_string()).into()),
+        }
+    }
+
+    fn as_position(index: &Literal, line: usize) -> Result<usize> {
+        match index {
+            Literal::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+            other => Err(InterpretError::InvalidIndex(line, other.to_string()).into()),
+        }
     }
 }
@@ -1,37 +1,142 @@
 #![allow(clippy::to_string_trait_impl)]
 mod ast;
+mod checker;
+mod dialect;
+mod doc;
+mod enums;
+mod environment;
 mod statements;
 mod interpreter;
+mod map;
 mod parser;
+mod range;
+mod resolver;
+mod runner;
 mod scanner;
+mod stdlib;
 mod test;
 mod token;
+mod translate;
 use crate::scanner::Scanner;
 use crate::parser::Parser;
-use anyhow::Result;
-use clap::Parser as ClapParser;
+use crate::resolver::Resolver;
+use crate::interpreter::{InterpretError, Interpreter};
+use crate::checker::TypeChecker;
+use crate::dialect::Dialect;
+use crate::doc::{DocFormat, ModuleDoc};
+use crate::runner::{TestFormat, TestResult};
+use crate::statements::Statement;
+use crate::stdlib::time::{DateValue, FakeClock};
+use anyhow::{anyhow, Result};
+use clap::{Parser as ClapParser, Subcommand};
 use std::fs::File;
 use std::io::Read;
 use std::io::{stdout, Write};
+use std::path::Path;
+use std::rc::Rc;
 use anyhow::Error;
 
 #[derive(ClapParser, Debug)]
 #[command(version, about, long_about=None)]
+#[command(override_usage = "brainrot [OPTIONS] [FILE_NAME] [SCRIPT_ARGS]...\n       brainrot [OPTIONS] <COMMAND>")]
 struct Args {
-    file_name: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
     #[arg(short, long, default_value_t = 1)]
     count: u8,
+    /// Seed for the random module, makes runs reproducible
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// Freeze the clock of the time module at an ISO-8601 date, sleeping
+    /// advances it without waiting
+    #[arg(long, global = true, value_name = "DATE")]
+    fake_clock: Option<String>,
+    /// Keyword dialect, a preset name or the path of a dialect file, a
+    /// `// dialect: name` comment at the top of a file overrides it
+    #[arg(long, global = true, default_value = "standard")]
+    dialect: String,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Type check a file without running it
+    Check { file_name: String },
+    /// Rewrite the keywords of a file into another dialect and print it
+    Translate {
+        /// Dialect the file is written in, defaults to its pragma or --dialect
+        #[arg(long)]
+        from: Option<String>,
+        /// Dialect to translate into
+        #[arg(long)]
+        to: String,
+        file_name: String,
+    },
+    /// Print the documentation of the declarations in the given files
+    Doc {
+        #[arg(long, value_enum, default_value_t = DocFormat::Markdown)]
+        format: DocFormat,
+        #[arg(required = true)]
+        file_names: Vec<String>,
+    },
+    /// Run the test blocks of *_test.br files, exits with 1 if any fails
+    Test {
+        /// Test files, or directories to search for them
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+        /// Only run tests whose name contains this text
+        #[arg(long)]
+        filter: Option<String>,
+        #[arg(long, value_enum, default_value_t = TestFormat::Human)]
+        format: TestFormat,
+        /// Write the report to a file instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+    // anything that is not one of the commands above is a script to run,
+    // followed by the arguments passed on to it as sys.args, so options go
+    // before the file name and command names after it belong to the script
+    #[command(external_subcommand)]
+    Run(Vec<String>),
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    let clock_start = match &args.fake_clock {
+        Some(date) => match DateValue::parse(date) {
+            Some(start) => Some(start),
+            None => return Err(anyhow!("invalid --fake-clock date '{date}'")),
+        },
+        None => None,
+    };
+    let options = InterpreterOptions { seed: args.seed, clock_start };
     let mut main = Main {
         scanner: Scanner::default(),
         parser: Parser::default(),
+        interpreter: options.build(),
+        options,
+        dialect: Rc::new(Dialect::load(&args.dialect)?),
     };
 
-    if let Some(file_name) = &args.file_name {
+    if let Some(Command::Check { file_name }) = &args.command {
+        if !main.check_file(file_name)? {
+            std::process::exit(1);
+        }
+    } else if let Some(Command::Translate { from, to, file_name }) = &args.command {
+        if !main.translate_file(file_name, from.as_deref(), to)? {
+            std::process::exit(1);
+        }
+    } else if let Some(Command::Doc { format, file_names }) = &args.command {
+        if !main.document_files(file_names, *format)? {
+            std::process::exit(1);
+        }
+    } else if let Some(Command::Test { paths, filter, format, output }) = &args.command {
+        if !main.test_files(paths, filter.as_deref(), *format, output.as_deref())? {
+            std::process::exit(1);
+        }
+    } else if let Some(Command::Run(words)) = &args.command {
+        let (file_name, script_args) = words.split_first().expect("clap passes the command name");
+        main.interpreter.set_args(script_args);
         main.run_file(file_name)?;
     } else {
         main.run_prompt()?;
@@ -39,13 +144,62 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+// what --seed and --fake-clock ask of every interpreter, the test command
+// builds a fresh one for each test
+#[derive(Clone, Copy)]
+struct InterpreterOptions {
+    seed: Option<u64>,
+    clock_start: Option<DateValue>,
+}
+
+impl InterpreterOptions {
+    fn build(&self) -> Interpreter {
+        let mut interpreter = Interpreter::new();
+        if let Some(seed) = self.seed {
+            interpreter.random.seed(seed);
+        }
+        if let Some(start) = self.clock_start {
+            interpreter.clock = Box::new(FakeClock::new(start));
+        }
+        interpreter
+    }
+}
+
+// the interpreter is kept between runs so that globals, and constants in
+// particular, carry over from one REPL line to the next
 struct Main {
     scanner: Scanner,
     parser: Parser,
+    interpreter: Interpreter,
+    options: InterpreterOptions,
+    // used for sources without a dialect pragma
+    dialect: Rc<Dialect>,
 }
 impl Main {
-    fn run(&mut self, str: &str) {
-        self.scanner = Scanner::new(str.to_string());
+    // scans, parses and resolves the source, printing every error found on
+    // the way, the statements are only returned if there were none. `dir` is
+    // where the source came from, a dialect pragma path is relative to it
+    fn front_end(&mut self, str: &str, dir: &Path) -> Option<Vec<Statement>> {
+        match self.analyze(str, dir) {
+            Ok(tree) => Some(tree),
+            Err(errors) => {
+                errors.iter().for_each(|e| println!("{e}"));
+                None
+            }
+        }
+    }
+
+    // the same as front_end, but hands back the messages of the errors
+    // instead of printing them
+    fn analyze(&mut self, str: &str, dir: &Path) -> std::result::Result<Vec<Statement>, Vec<String>> {
+        let dialect = match dialect::pragma(str) {
+            Some(name) => match Dialect::load_from(name, dir) {
+                Ok(dialect) => Rc::new(dialect),
+                Err(e) => return Err(vec![e.to_string()]),
+            },
+            None => self.dialect.clone(),
+        };
+        self.scanner = Scanner::with_dialect(str.to_string(), dialect);
 
         self.scanner.scan_tokens();
         // TODO: remove this clone call
@@ -53,34 +207,140 @@ impl Main {
 
         let tree = self.parser.parse();
 
+        let mut resolver = Resolver::new(self.interpreter.environment.get_constants().clone());
+        resolver.resolve(&tree);
+
         let scanner_errors = self.scanner.get_errors(); // -> &Vec<Error>
         let parser_errors = self.parser.get_errors(); // -> &Vec<Error>
+        let resolver_errors = resolver.get_errors(); // -> &Vec<Error>
         let parsing_errors: Vec<&Error> = scanner_errors
                                         .iter()
                                         .chain(parser_errors.iter())
+                                        .chain(resolver_errors.iter())
                                         .collect();
         if !parsing_errors.is_empty() {
-            parsing_errors.iter().for_each(|e| println!("{e:?}"));
+            return Err(parsing_errors.iter().map(|e| e.to_string()).collect());
         }
-        else {
-            let mut interpreter = interpreter::Interpreter::new();
-            let _result = interpreter.interpret(tree);
+        Ok(tree)
+    }
+
+    fn run(&mut self, str: &str, dir: &Path) {
+        let Some(tree) = self.front_end(str, dir) else {
+            return;
+        };
 
-            if !interpreter.errors.is_empty() {
-                println!("{:?}", interpreter.errors);
+        if let Err(e) = self.interpreter.interpret(tree) {
+            if let Some(InterpretError::Exit(_, code)) = e.downcast_ref() {
+                let _ = stdout().flush();
+                std::process::exit(*code);
             }
+            self.interpreter.errors.push(e);
+        }
+
+        if !self.interpreter.errors.is_empty() {
+            println!("{:?}", self.interpreter.errors);
+            self.interpreter.errors.clear();
         }
     }
 
-    fn run_file(&mut self, path: &String) -> Result<()> {
+    // the directory a file is in, relative paths in the file are taken from
+    // there
+    fn directory(path: &str) -> &Path {
+        Path::new(path).parent().unwrap_or(Path::new(""))
+    }
+
+    fn read_file(path: &String) -> Result<String> {
         let mut file: File = File::open(path)?;
         let mut contents = String::new();
         let _ = file.read_to_string(&mut contents)?;
+        Ok(contents)
+    }
 
-        self.run(&contents);
+    fn run_file(&mut self, path: &String) -> Result<()> {
+        let contents = Self::read_file(path)?;
+
+        self.run(&contents, Self::directory(path));
         Ok(())
     }
 
+    // returns whether the file passed every check
+    fn check_file(&mut self, path: &String) -> Result<bool> {
+        let contents = Self::read_file(path)?;
+        let Some(tree) = self.front_end(&contents, Self::directory(path)) else {
+            return Ok(false);
+        };
+
+        let mut checker = TypeChecker::new();
+        checker.check(&tree);
+        let errors = checker.get_errors();
+        errors.iter().for_each(|e| println!("{e}"));
+        Ok(errors.is_empty())
+    }
+
+    // prints the translation, or the errors that prevented it, and returns
+    // whether there was one
+    fn translate_file(&mut self, path: &String, from: Option<&str>, to: &str) -> Result<bool> {
+        let contents = Self::read_file(path)?;
+        let from = match (from, dialect::pragma(&contents)) {
+            (Some(name), _) => Rc::new(Dialect::load(name)?),
+            (None, Some(name)) => Rc::new(Dialect::load_from(name, Self::directory(path))?),
+            (None, None) => self.dialect.clone(),
+        };
+        let target = Dialect::load(to)?;
+
+        match translate::translate(&contents, from, &target, to) {
+            Ok(translated) => {
+                print!("{translated}");
+                Ok(true)
+            }
+            Err(errors) => {
+                errors.iter().for_each(|e| println!("{e}"));
+                Ok(false)
+            }
+        }
+    }
+
+    // prints the documentation of every file, or nothing if any of them has
+    // errors, and returns whether it was printed
+    fn document_files(&mut self, paths: &[String], format: DocFormat) -> Result<bool> {
+        let mut modules = vec![];
+        for path in paths {
+            let contents = Self::read_file(path)?;
+            let Some(tree) = self.front_end(&contents, Self::directory(path)) else {
+                return Ok(false);
+            };
+            let name = Path::new(path)
+                .file_stem()
+                .map_or(path.clone(), |stem| stem.to_string_lossy().into_owned());
+            modules.push(ModuleDoc::new(&name, self.parser.get_module_doc(), &tree));
+        }
+        print!("{}", doc::render(&modules, format));
+        Ok(true)
+    }
+
+    // runs and reports every test, a file that does not parse counts as a
+    // failed test, returns whether all of them passed
+    fn test_files(&mut self, paths: &[String], filter: Option<&str>, format: TestFormat, output: Option<&str>) -> Result<bool> {
+        let mut results = vec![];
+        for path in runner::discover(paths)? {
+            let contents = Self::read_file(&path)?;
+            match self.analyze(&contents, Self::directory(&path)) {
+                Ok(tree) => {
+                    let options = self.options;
+                    results.extend(runner::run_tests(&path, tree, filter, || options.build()));
+                }
+                Err(errors) => results.push(TestResult::file_failure(&path, &errors)),
+            }
+        }
+
+        let report = runner::report(&results, format);
+        match output {
+            Some(output) => std::fs::write(output, report)?,
+            None => print!("{report}"),
+        }
+        Ok(results.iter().all(|result| result.failure.is_none()))
+    }
+
     fn run_prompt(&mut self) -> Result<()> {
         let input = std::io::stdin();
         let mut content = String::new();
@@ -93,7 +353,7 @@ impl Main {
                 println!();
                 break;
             }
-            self.run(content.trim());
+            self.run(content.trim(), Path::new(""));
         }
         Ok(())
     }
@@ -0,0 +1,48 @@
+use crate::token::Literal;
+use std::collections::HashMap;
+
+// a read-only mapping from string keys to values that keeps the order its
+// entries were inserted in, built by natives like json.parse, `map.key` and
+// `map["key"]` read an entry
+#[derive(Debug, Clone, Default)]
+pub struct MapValue {
+    entries: Vec<(String, Literal)>,
+    // the position of every key in entries
+    index: HashMap<String, usize>,
+}
+
+impl MapValue {
+    // a later entry for a key replaces the earlier one but keeps its position
+    pub fn insert(&mut self, key: String, value: Literal) {
+        match self.index.get(&key) {
+            Some(&position) => self.entries[position].1 = value,
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Literal> {
+        self.index.get(key).map(|&position| &self.entries[position].1)
+    }
+
+    pub fn entries(&self) -> &[(String, Literal)] {
+        &self.entries
+    }
+}
+
+// maps are equal when they hold the same entries, in any order
+impl PartialEq for MapValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries.len() == other.entries.len()
+            && self.entries.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl std::fmt::Display for MapValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner: Vec<String> = self.entries.iter().map(|(k, v)| format!("{k}: {v}")).collect();
+        write!(f, "{{{}}}", inner.join(", "))
+    }
+}
@@ -1,9 +1,10 @@
 #![allow(dead_code)]
 use crate::ast::*;
 use crate::token::*;
-use crate::statements::Statement;
+use crate::statements::{EnumVariant, Statement};
 use anyhow::Error;
 use anyhow::Result;
+use std::collections::HashMap;
 
 // TODO:
 #[derive(thiserror::Error, Debug)]
@@ -18,6 +19,9 @@ pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
     errors: Vec<Error>,
+    // doc comments by the index of the token they come before
+    docs: HashMap<usize, String>,
+    module_doc: Option<String>,
 }
 
 impl Parser {
@@ -27,16 +31,45 @@ impl Parser {
         TokenType::GreaterEqual,
         TokenType::Less,
         TokenType::LessEqual,
+        TokenType::In,
     ];
     const TERM_TOKENS: &[TokenType] = &[TokenType::Plus, TokenType::Minus];
     const FACTOR_TOKENS: &[TokenType] = &[TokenType::Star, TokenType::Slash];
     const UNARY_TOKENS: &[TokenType] = &[TokenType::Bang, TokenType::Minus];
     const STATEMENT_TOKENS: &[TokenType] = &[TokenType::Print];
+    // doc comments are taken out of the token stream, consecutive `///` lines
+    // are joined and kept for the token after them, `//!` lines count only
+    // before the first token
     pub fn new(tokens: Vec<Token>) -> Self {
+        let mut kept = vec![];
+        let mut docs = HashMap::new();
+        let mut module_doc: Vec<String> = vec![];
+        let mut pending: Vec<String> = vec![];
+        for mut token in tokens {
+            if *token.get_type() != TokenType::DocComment {
+                if !pending.is_empty() {
+                    docs.insert(kept.len(), pending.join("\n"));
+                    pending.clear();
+                }
+                kept.push(token);
+                continue;
+            }
+            let Literal::String(text) = token.get_literal().clone() else {
+                continue;
+            };
+            if !token.lexeme.starts_with("//!") {
+                pending.push(text);
+            } else if kept.is_empty() && pending.is_empty() {
+                module_doc.push(text);
+            }
+        }
+
         Self {
-            tokens,
+            tokens: kept,
             current: 0,
             errors: Vec::new(),
+            docs,
+            module_doc: (!module_doc.is_empty()).then(|| module_doc.join("\n")),
         }
     }
 
@@ -44,27 +77,220 @@ impl Parser {
         &self.errors
     }
 
+    pub fn get_module_doc(&self) -> Option<&str> {
+        self.module_doc.as_deref()
+    }
+
+    // the doc comment right before the current token
+    fn take_doc(&mut self) -> Option<String> {
+        self.docs.remove(&self.current)
+    }
+
     pub fn parse(&mut self) -> Vec<Statement> {
         let mut statements = vec![];
 
         while !self.is_at_end() {
-            match self.statement() {
+            match self.declaration() {
                 Ok(s) => statements.push(s),
-                Err(e) => self.errors.push(e),
+                Err(e) => {
+                    self.errors.push(e);
+                    self.synchronize();
+                }
             }
         }
 
         statements
     }
 
+    fn declaration(&mut self) -> Result<Statement> {
+        let doc = self.take_doc();
+        if self.match_tokens(&[TokenType::Var]) {
+            return self.var_declaration(doc);
+        }
+        if self.match_tokens(&[TokenType::Const]) {
+            return self.const_declaration(doc);
+        }
+        if self.match_tokens(&[TokenType::Enum]) {
+            return self.enum_declaration(doc);
+        }
+        // `test` is only a keyword right before the name of a test
+        if self.peek().lexeme == "test" && *self.peek_next().get_type() == TokenType::String {
+            self.advance();
+            return self.test_block();
+        }
+
+        self.statement()
+    }
+
+    // enum Name { Variant, Variant(field, ...), ... }
+    fn enum_declaration(&mut self, doc: Option<String>) -> Result<Statement> {
+        let name = self.consume(TokenType::Identifier, "Expect enum name")?;
+        self.consume(TokenType::LeftBrace, "Expect { before enum variants")?;
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let variant_doc = self.take_doc();
+            let variant = self.consume(TokenType::Identifier, "Expect variant name")?;
+            if variants.iter().any(|v| v.name.lexeme == variant.lexeme) {
+                let message = format!("Duplicate variant {} in enum {}", variant.lexeme, name.lexeme);
+                return Err(ParserError::GenericMessage(variant.get_line(), message).into());
+            }
+
+            let fields = if self.match_tokens(&[TokenType::LeftParen]) {
+                let mut fields: Vec<Token> = vec![];
+                if !self.check(TokenType::RightParen) {
+                    loop {
+                        let field = self.consume(TokenType::Identifier, "Expect field name")?;
+                        if fields.iter().any(|f| f.lexeme == field.lexeme) {
+                            let message = format!("Duplicate field {} in variant {}", field.lexeme, variant.lexeme);
+                            return Err(ParserError::GenericMessage(field.get_line(), message).into());
+                        }
+                        fields.push(field);
+                        if !self.match_tokens(&[TokenType::Comma]) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expect ) after variant fields")?;
+                Some(fields)
+            } else {
+                None
+            };
+            variants.push(EnumVariant { name: variant, fields, doc: variant_doc });
+
+            if !self.match_tokens(&[TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect } after enum variants")?;
+        Ok(Statement::Enum(name, variants, doc))
+    }
+
+    fn var_declaration(&mut self, doc: Option<String>) -> Result<Statement> {
+        let pattern = self.pattern()?;
+        let annotation = self.type_annotation()?;
+
+        let initializer = if self.match_tokens(&[TokenType::Equal]) {
+            self.expression()
+        } else if let Pattern::Tuple(paren, _) = &pattern {
+            let line = paren.get_line();
+            return Err(ParserError::GenericMessage(line, "Expect = after destructuring pattern".to_string()).into());
+        } else {
+            LiteralExpr::make_expr(Literal::Nil)
+        };
+
+        self.consume(TokenType::SemiColon, "Expect ; after variable declaration")?;
+        Ok(Statement::Var(pattern, annotation, initializer, doc))
+    }
+
+    fn const_declaration(&mut self, doc: Option<String>) -> Result<Statement> {
+        let pattern = self.pattern()?;
+        let annotation = self.type_annotation()?;
+        self.consume(TokenType::Equal, "Expect = after constant name")?;
+        let initializer = self.expression();
+        self.consume(TokenType::SemiColon, "Expect ; after constant declaration")?;
+        Ok(Statement::Const(pattern, annotation, initializer, doc))
+    }
+
+    // ( ":" type )?
+    fn type_annotation(&mut self) -> Result<Option<TypeAnnotation>> {
+        if self.match_tokens(&[TokenType::Colon]) {
+            return Ok(Some(self.type_name()?));
+        }
+        Ok(None)
+    }
+
+    // Name | nil | ( type, type, ... )
+    fn type_name(&mut self) -> Result<TypeAnnotation> {
+        if self.match_tokens(&[TokenType::Identifier, TokenType::Nil]) {
+            return Ok(TypeAnnotation::Named(self.previous()));
+        }
+
+        if self.match_tokens(&[TokenType::LeftParen]) {
+            let paren = self.previous();
+            let mut annotations = vec![];
+            if !self.check(TokenType::RightParen) {
+                loop {
+                    annotations.push(self.type_name()?);
+                    if !self.match_tokens(&[TokenType::Comma]) || self.check(TokenType::RightParen) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightParen, "Expect ) after tuple type")?;
+            return Ok(TypeAnnotation::Tuple(paren, annotations));
+        }
+
+        let line = self.peek().get_line();
+        Err(ParserError::GenericMessage(line, "Expect type name".to_string()).into())
+    }
+
+    // name | ( pattern, pattern, ... )
+    fn pattern(&mut self) -> Result<Pattern> {
+        if self.match_tokens(&[TokenType::Identifier]) {
+            return Ok(Pattern::Name(self.previous()));
+        }
+
+        if self.match_tokens(&[TokenType::LeftParen]) {
+            let paren = self.previous();
+            let mut patterns = vec![];
+            if !self.check(TokenType::RightParen) {
+                loop {
+                    patterns.push(self.pattern()?);
+                    if !self.match_tokens(&[TokenType::Comma]) || self.check(TokenType::RightParen) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightParen, "Expect ) after destructuring pattern")?;
+            return Ok(Pattern::Tuple(paren, patterns));
+        }
+
+        let line = self.peek().get_line();
+        Err(ParserError::GenericMessage(line, "Expect variable name or pattern".to_string()).into())
+    }
+
+    // test "name" { declaration* }
+    fn test_block(&mut self) -> Result<Statement> {
+        let name = self.advance();
+        self.consume(TokenType::LeftBrace, "Expect { before test body")?;
+        let mut body = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let statement = self.declaration()?;
+            if let Statement::Test(inner, _) = &statement {
+                return Err(ParserError::GenericMessage(inner.get_line(), "Tests cannot be nested".to_string()).into());
+            }
+            body.push(statement);
+        }
+        self.consume(TokenType::RightBrace, "Expect } after test body")?;
+        Ok(Statement::Test(name, body))
+    }
+
     fn statement(&mut self) -> Result<Statement> {
         if self.match_tokens(Self::STATEMENT_TOKENS) {
             return self.print_statement();
         }
+        if self.match_tokens(&[TokenType::Assert]) {
+            return self.assert_statement();
+        }
 
         self.expression_statement()
     }
 
+    // assert condition ("," message)? ";"
+    fn assert_statement(&mut self) -> Result<Statement> {
+        let keyword = self.previous();
+        let condition = self.expression();
+        let message = if self.match_tokens(&[TokenType::Comma]) {
+            Some(self.expression())
+        } else {
+            None
+        };
+        self.consume(TokenType::SemiColon, "Expect ; after assertion")?;
+        Ok(Statement::Assert(keyword, condition, message))
+    }
+
     fn print_statement(&mut self) -> Result<Statement> {
         let value = self.expression();
         self.consume(TokenType::SemiColon, "Expect ; after value")?;
@@ -78,7 +304,56 @@ impl Parser {
     }
 
     fn expression(&mut self) -> Expr {
-        self.equality()
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Expr {
+        let expr = self.coalesce();
+
+        if self.match_tokens(&[TokenType::Equal]) {
+            let equals = self.previous();
+            let value = self.assignment();
+
+            return match Self::assignment_target(expr) {
+                Some(target) => Assign::make_expr(target, value),
+                None => {
+                    let error = ParserError::GenericMessage(equals.get_line(), "Invalid assignment target".to_string());
+                    self.errors.push(error.into());
+                    value
+                }
+            };
+        }
+
+        expr
+    }
+
+    // turns the already parsed left hand side of an `=` back into a pattern
+    fn assignment_target(expr: Expr) -> Option<Pattern> {
+        match expr {
+            Expr::Var(v) => Some(Pattern::Name(v.name)),
+            Expr::Grouping(g) => Self::assignment_target(*g.expression),
+            Expr::Tuple(t) => {
+                let patterns = t
+                    .elements
+                    .into_iter()
+                    .map(Self::assignment_target)
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Pattern::Tuple(t.paren, patterns))
+            }
+            _ => None,
+        }
+    }
+
+    fn coalesce(&mut self) -> Expr {
+        let mut expr = self.equality();
+
+        // ??
+        while self.match_tokens(&[TokenType::QuestionQuestion]) {
+            let operator = self.previous();
+            let right = self.equality();
+            expr = Binary::make_expr(expr, operator, right);
+        }
+        expr
     }
 
     fn equality(&mut self) -> Expr {
@@ -94,17 +369,49 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Expr {
-        let mut expr = self.term();
+        let mut expr = self.range();
 
-        // > >= < <=
-        while self.match_tokens(Self::COMPARISON_TOKENS) {
-            let operator = self.previous();
-            let right = self.term();
+        // > >= < <= in not in
+        while let Some(operator) = self.comparison_operator() {
+            let right = self.range();
             expr = Binary::make_expr(expr, operator, right);
         }
         expr
     }
 
+    fn comparison_operator(&mut self) -> Option<Token> {
+        if self.match_tokens(Self::COMPARISON_TOKENS) {
+            return Some(self.previous());
+        }
+        if self.check(TokenType::Not) && *self.peek_next().get_type() == TokenType::In {
+            let not = self.advance();
+            self.advance();
+            return Some(Token::new(TokenType::NotIn, "not in".to_string(), None, not.get_line()));
+        }
+        None
+    }
+
+    // term ( ( ".." | "..=" ) term ( step term )? )?
+    fn range(&mut self) -> Expr {
+        let expr = self.term();
+
+        if self.match_tokens(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+            let operator = self.previous();
+            let end = self.term();
+            // `step` is only a keyword right after a range, it stays usable as
+            // a variable name everywhere else
+            let step = if self.check(TokenType::Identifier) && self.peek().lexeme == "step" {
+                self.advance();
+                Some(self.term())
+            } else {
+                None
+            };
+            return RangeExpr::make_expr(expr, operator, end, step);
+        }
+
+        expr
+    }
+
     fn term(&mut self) -> Expr {
         let mut expr = self.factor();
 
@@ -134,11 +441,71 @@ impl Parser {
         // ! -
         if self.match_tokens(Self::UNARY_TOKENS) {
             let operator = self.previous();
-            let right = self.primary();
+            let right = self.call();
             return Unary::make_expr(operator, right);
         }
 
-        self.primary()
+        self.call()
+    }
+
+    // primary ( "(" arguments? ")" | ( "." | "?." ) name | ( "[" | "?[" ) expr "]" )*
+    fn call(&mut self) -> Expr {
+        let mut expr = self.primary();
+        let mut optional = false;
+
+        loop {
+            if self.match_tokens(&[TokenType::LeftParen]) {
+                expr = self.finish_call(expr);
+            } else if self.match_tokens(&[TokenType::Dot, TokenType::QuestionDot]) {
+                let is_optional = *self.previous().get_type() == TokenType::QuestionDot;
+                optional |= is_optional;
+                match self.consume(TokenType::Identifier, "Expect property name after .") {
+                    Ok(name) => expr = Get::make_expr(expr, name, is_optional),
+                    Err(e) => {
+                        self.errors.push(e.into());
+                        break;
+                    }
+                }
+            } else if self.match_tokens(&[TokenType::LeftBracket, TokenType::QuestionLeftBracket]) {
+                let bracket = self.previous();
+                let is_optional = *bracket.get_type() == TokenType::QuestionLeftBracket;
+                optional |= is_optional;
+                let index = self.expression();
+                if let Err(e) = self.consume(TokenType::RightBracket, "Expect ] after index") {
+                    self.errors.push(e.into());
+                    break;
+                }
+                expr = Index::make_expr(expr, bracket, index, is_optional);
+            } else {
+                break;
+            }
+        }
+
+        if optional {
+            return OptionalChain::make_expr(expr);
+        }
+        expr
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Expr {
+        let mut arguments = vec![];
+        if !self.check(TokenType::RightParen) {
+            loop {
+                arguments.push(self.element());
+                if !self.match_tokens(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        let paren = match self.consume(TokenType::RightParen, "Expect ) after arguments") {
+            Ok(paren) => paren,
+            Err(e) => {
+                self.errors.push(e.into());
+                self.previous()
+            }
+        };
+        Call::make_expr(callee, paren, arguments)
     }
 
     fn primary(&mut self) -> Expr {
@@ -156,27 +523,65 @@ impl Parser {
             return LiteralExpr::make_expr(self.previous().get_literal().clone());
         }
 
+        if self.match_tokens(&[TokenType::Identifier]) {
+            return Variable::make_expr(self.previous());
+        }
+
         if self.match_tokens(&[TokenType::LeftParen]) {
-            let expr = self.expression();
-            let res = self.consume(TokenType::RightParen, "Expect ) after expression");
-            if let Err(e) = res {
-                self.errors.push(e.into());
-            }
-            return Grouping::make_expr(expr);
+            return self.grouping_or_tuple();
         }
-        let get_token_loc = self.peek().clone().get_line();
+        let get_token_loc = self.peek().get_line();
         let error = ParserError::InvalidSyntax(get_token_loc);
         self.errors.push(error.into());
 
         LiteralExpr::make_expr(Literal::Nil)
     }
 
+    // ( expr ) is a grouping, () ( expr, ) ( ...expr ) and ( expr, expr, ... )
+    // are tuples
+    fn grouping_or_tuple(&mut self) -> Expr {
+        let paren = self.previous();
+        if self.match_tokens(&[TokenType::RightParen]) {
+            return Tuple::make_expr(paren, vec![]);
+        }
+
+        let expr = self.element();
+        let is_tuple = self.check(TokenType::Comma) || matches!(expr, Expr::Spread(_));
+        let mut elements = vec![expr];
+        while self.match_tokens(&[TokenType::Comma]) {
+            if self.check(TokenType::RightParen) {
+                break;
+            }
+            elements.push(self.element());
+        }
+
+        let res = self.consume(TokenType::RightParen, "Expect ) after expression");
+        if let Err(e) = res {
+            self.errors.push(e.into());
+        }
+
+        if is_tuple {
+            Tuple::make_expr(paren, elements)
+        } else {
+            Grouping::make_expr(elements.pop().unwrap())
+        }
+    }
+
+    // an expression that may be spread with `...`
+    fn element(&mut self) -> Expr {
+        if self.match_tokens(&[TokenType::Ellipsis]) {
+            let ellipsis = self.previous();
+            return Spread::make_expr(ellipsis, self.expression());
+        }
+        self.expression()
+    }
+
     // TODO:
     fn consume(&mut self, token_type: TokenType, err_str: &str) -> Result<Token, ParserError> {
         if self.check(token_type) {
             return Ok(self.advance());
         }
-        let get_token_loc = self.peek().clone().get_line();
+        let get_token_loc = self.peek().get_line();
         Err(ParserError::GenericMessage(get_token_loc, err_str.to_string()))
     }
 
@@ -211,19 +616,31 @@ impl Parser {
     fn peek(&self) -> &Token {
         &self.tokens[self.current]
     }
+    fn peek_next(&self) -> &Token {
+        if self.is_at_end() {
+            return self.peek();
+        }
+        &self.tokens[self.current + 1]
+    }
 
     fn synchronize(&mut self) {
         self.advance();
 
         while !self.is_at_end() {
+            if *self.previous().get_type() == TokenType::SemiColon {
+                return;
+            }
             match self.peek().get_type() {
                 TokenType::Class => return,
+                TokenType::Enum => return,
                 TokenType::Fun => return,
                 TokenType::Var => return,
+                TokenType::Const => return,
                 TokenType::For => return,
                 TokenType::If => return,
                 TokenType::While => return,
                 TokenType::Print => return,
+                TokenType::Assert => return,
                 TokenType::Return => return,
                 _ => (),
             }
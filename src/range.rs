@@ -0,0 +1,76 @@
+// the most numbers a range expands into when it is spread, passed to the iter
+// module or written as JSON, so that an unbounded looking range fails instead
+// of exhausting memory
+pub const MAX_EXPANDED: usize = 1 << 20;
+
+// how far, relative to its size, a number of steps may be off a whole number
+// and still count as one, (0.9 - 0) / 0.3 is 2.9999998 in f32
+const TOLERANCE: f32 = 1e-4;
+
+// the value of `start..end` or `start..=end`, optionally with a `step`, the
+// numbers in it are computed on demand so a range never allocates
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeValue {
+    pub start: f32,
+    pub end: f32,
+    pub step: f32,
+    pub inclusive: bool,
+}
+
+impl RangeValue {
+    pub fn len(&self) -> usize {
+        let span = steps((self.end - self.start) / self.step);
+        if span < 0.0 {
+            return 0;
+        }
+        if self.inclusive {
+            span.floor() as usize + 1
+        } else {
+            span.ceil() as usize
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<f32> {
+        (index < self.len()).then(|| self.value(index))
+    }
+
+    // an inclusive range whose steps land on its end gives exactly the end,
+    // rather than the sum of the steps with their rounding errors
+    fn value(&self, index: usize) -> f32 {
+        if self.inclusive && index as f32 == steps((self.end - self.start) / self.step) {
+            self.end
+        } else {
+            self.start + self.step * index as f32
+        }
+    }
+
+    pub fn contains(&self, value: f32) -> bool {
+        let offset = steps((value - self.start) / self.step);
+        offset >= 0.0 && offset.fract() == 0.0 && (offset as usize) < self.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = f32> + '_ {
+        (0..self.len()).map(|i| self.value(i))
+    }
+}
+
+// rounds a number of steps that is within the tolerance of a whole number
+fn steps(n: f32) -> f32 {
+    let whole = n.round();
+    if (n - whole).abs() <= TOLERANCE * whole.abs().max(1.0) {
+        whole
+    } else {
+        n
+    }
+}
+
+impl std::fmt::Display for RangeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{operator}{}", self.start, self.end)?;
+        if self.step != 1.0 {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use crate::ast::{Expr, Pattern};
+use crate::statements::Statement;
+use crate::token::Token;
+use anyhow::Error;
+use std::collections::HashSet;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ResolverError {
+    #[error("[{0}]: Cannot assign to constant '{1}'")]
+    AssignToConstant(usize, String),
+    #[error("[{0}]: Cannot redefine constant '{1}'")]
+    RedefineConstant(usize, String),
+}
+
+// static pass over the parsed statements that runs before the interpreter,
+// it reports the errors that can be found without executing anything
+pub struct Resolver {
+    constants: HashSet<String>,
+    errors: Vec<Error>,
+}
+
+impl Resolver {
+    // `constants` are the names that are already constant before these
+    // statements run, e.g. from earlier lines in the REPL
+    pub fn new(constants: HashSet<String>) -> Self {
+        Self {
+            constants,
+            errors: vec![],
+        }
+    }
+
+    pub fn get_errors(&self) -> &Vec<Error> {
+        &self.errors
+    }
+
+    pub fn resolve(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Expression(e) | Statement::Print(e) => self.resolve_expr(e),
+            Statement::Var(pattern, _, e, _) => {
+                self.resolve_expr(e);
+                self.declare(pattern, false);
+            }
+            Statement::Const(pattern, _, e, _) => {
+                self.resolve_expr(e);
+                self.declare(pattern, true);
+            }
+            Statement::Enum(name, _, _) => self.declare_name(name, false),
+            Statement::Assert(_, condition, message) => {
+                self.resolve_expr(condition);
+                if let Some(message) = message {
+                    self.resolve_expr(message);
+                }
+            }
+            // every test runs on its own, so constants declared in one are
+            // not visible to the next
+            Statement::Test(_, body) => {
+                let constants = self.constants.clone();
+                self.resolve(body);
+                self.constants = constants;
+            }
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Binary(b) => {
+                self.resolve_expr(&b.left);
+                self.resolve_expr(&b.right);
+            }
+            Expr::Unary(u) => self.resolve_expr(&u.next),
+            Expr::Literal(_) | Expr::Var(_) => (),
+            Expr::Grouping(g) => self.resolve_expr(&g.expression),
+            Expr::Assign(a) => {
+                self.resolve_expr(&a.value);
+                self.check_assignment(&a.target);
+            }
+            Expr::Tuple(t) => t.elements.iter().for_each(|e| self.resolve_expr(e)),
+            Expr::Call(c) => {
+                self.resolve_expr(&c.callee);
+                c.arguments.iter().for_each(|a| self.resolve_expr(a));
+            }
+            Expr::Get(g) => self.resolve_expr(&g.object),
+            Expr::Spread(s) => self.resolve_expr(&s.expression),
+            Expr::Index(i) => {
+                self.resolve_expr(&i.object);
+                self.resolve_expr(&i.index);
+            }
+            Expr::OptionalChain(o) => self.resolve_expr(&o.expression),
+            Expr::Range(r) => {
+                self.resolve_expr(&r.start);
+                self.resolve_expr(&r.end);
+                if let Some(step) = &r.step {
+                    self.resolve_expr(step);
+                }
+            }
+        }
+    }
+
+    fn declare(&mut self, pattern: &Pattern, constant: bool) {
+        match pattern {
+            Pattern::Name(name) => self.declare_name(name, constant),
+            Pattern::Tuple(_, patterns) => patterns.iter().for_each(|p| self.declare(p, constant)),
+        }
+    }
+
+    fn declare_name(&mut self, name: &Token, constant: bool) {
+        if self.constants.contains(&name.lexeme) {
+            let error = ResolverError::RedefineConstant(name.get_line(), name.lexeme.clone());
+            self.errors.push(error.into());
+        } else if constant {
+            self.constants.insert(name.lexeme.clone());
+        }
+    }
+
+    fn check_assignment(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Name(name) => {
+                if self.constants.contains(&name.lexeme) {
+                    let error = ResolverError::AssignToConstant(name.get_line(), name.lexeme.clone());
+                    self.errors.push(error.into());
+                }
+            }
+            Pattern::Tuple(_, patterns) => patterns.iter().for_each(|p| self.check_assignment(p)),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::interpreter::Interpreter;
+use crate::statements::Statement;
+use anyhow::Result;
+use std::path::Path;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum TestFormat {
+    Human,
+    Tap,
+    Junit,
+}
+
+pub struct TestResult {
+    pub file: String,
+    pub name: String,
+    // of the test's name, or of the first error when the whole file failed
+    pub line: usize,
+    // the error that ended the test, None if it passed
+    pub failure: Option<String>,
+}
+
+impl TestResult {
+    // a file that could not be run at all, at the line of its first error
+    pub fn file_failure(file: &str, errors: &[String]) -> Self {
+        Self {
+            file: file.to_string(),
+            name: "(file)".to_string(),
+            line: errors.first().map_or(0, |error| error_line(error)),
+            failure: Some(errors.join("; ")),
+        }
+    }
+}
+
+// errors start with `[line]:` or `[line, column]:`, 0 for those without one
+fn error_line(error: &str) -> usize {
+    let digits: String = error
+        .strip_prefix('[')
+        .unwrap_or_default()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().unwrap_or(0)
+}
+
+// every `*_test.br` file in the given files and directories, directories are
+// searched recursively, in a stable order
+pub fn discover(paths: &[String]) -> Result<Vec<String>> {
+    let mut files = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            collect(path, &mut files)?;
+        } else {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+    Ok(files)
+}
+
+fn collect(dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)?.collect::<std::io::Result<_>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, files)?;
+        } else if path.to_string_lossy().ends_with("_test.br") {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+    Ok(())
+}
+
+// runs the tests of a parsed file whose name contains `filter`, each in a new
+// interpreter from `new_interpreter` that first runs the top level statements
+// of the file
+pub fn run_tests(
+    file: &str,
+    statements: Vec<Statement>,
+    filter: Option<&str>,
+    new_interpreter: impl Fn() -> Interpreter,
+) -> Vec<TestResult> {
+    let (tests, setup): (Vec<Statement>, Vec<Statement>) = statements
+        .into_iter()
+        .partition(|statement| matches!(statement, Statement::Test(..)));
+
+    let mut results = vec![];
+    for test in tests {
+        let Statement::Test(mut name, body) = test else {
+            continue;
+        };
+        // the parser only accepts a string token as the name
+        let name_text = name.get_literal().to_string();
+        if filter.is_some_and(|filter| !name_text.contains(filter)) {
+            continue;
+        }
+
+        let mut interpreter = new_interpreter();
+        let outcome = interpreter
+            .interpret(setup.clone())
+            .and_then(|_| interpreter.interpret(body));
+        results.push(TestResult {
+            file: file.to_string(),
+            name: name_text,
+            line: name.get_line(),
+            failure: outcome.err().map(|e| e.to_string()),
+        });
+    }
+    results
+}
+
+pub fn report(results: &[TestResult], format: TestFormat) -> String {
+    match format {
+        TestFormat::Human => human(results),
+        TestFormat::Tap => tap(results),
+        TestFormat::Junit => junit(results),
+    }
+}
+
+fn human(results: &[TestResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        let location = match result.line {
+            0 => result.file.clone(),
+            line => format!("{}:{line}", result.file),
+        };
+        match &result.failure {
+            None => out.push_str(&format!("PASS {location} {}\n", result.name)),
+            Some(failure) => {
+                out.push_str(&format!("FAIL {location} {}\n", result.name));
+                out.push_str(&format!("    {}: {failure}\n", result.file));
+            }
+        }
+    }
+    let failed = results.iter().filter(|result| result.failure.is_some()).count();
+    out.push_str(&format!("\n{} passed, {failed} failed\n", results.len() - failed));
+    out
+}
+
+// TAP version 13, failures carry a YAML block with the message and location
+fn tap(results: &[TestResult]) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", results.len());
+    for (i, result) in results.iter().enumerate() {
+        let description = format!("{} > {}", result.file, result.name);
+        match &result.failure {
+            None => out.push_str(&format!("ok {} - {description}\n", i + 1)),
+            Some(failure) => {
+                out.push_str(&format!("not ok {} - {description}\n", i + 1));
+                out.push_str("  ---\n");
+                out.push_str(&format!("  message: '{}'\n", failure.replace('\'', "''")));
+                out.push_str(&format!("  file: '{}'\n", result.file.replace('\'', "''")));
+                out.push_str(&format!("  line: {}\n", result.line));
+                out.push_str("  ...\n");
+            }
+        }
+    }
+    out
+}
+
+// one testsuite per file
+fn junit(results: &[TestResult]) -> String {
+    let failed = results.iter().filter(|result| result.failure.is_some()).count();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!("<testsuites tests=\"{}\" failures=\"{failed}\">\n", results.len()));
+
+    let mut files: Vec<&str> = vec![];
+    for result in results {
+        if !files.contains(&result.file.as_str()) {
+            files.push(&result.file);
+        }
+    }
+    for file in files {
+        let suite: Vec<&TestResult> = results.iter().filter(|result| result.file == file).collect();
+        let failed = suite.iter().filter(|result| result.failure.is_some()).count();
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failed}\">\n",
+            escape(file),
+            suite.len()
+        ));
+        for result in suite {
+            let attributes = format!(
+                "name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{}\"",
+                escape(&result.name),
+                escape(file),
+                escape(file),
+                result.line
+            );
+            match &result.failure {
+                None => out.push_str(&format!("    <testcase {attributes}/>\n")),
+                Some(failure) => {
+                    out.push_str(&format!("    <testcase {attributes}>\n"));
+                    out.push_str(&format!("      <failure message=\"{}\"/>\n", escape(failure)));
+                    out.push_str("    </testcase>\n");
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
@@ -1,19 +1,28 @@
 #![allow(dead_code)]
 
+use crate::dialect::Dialect;
 use crate::token::{Literal, Token, TokenType};
 use anyhow::{Error, Result};
+use std::ops::Range;
+use std::rc::Rc;
 use phf_macros::phf_map;
 use thiserror::Error;
 
-const KEYWORDS: phf::Map<&'static str, TokenType> = phf_map! {
+// the standard spelling of every keyword, dialects respell them
+pub const KEYWORDS: phf::Map<&'static str, TokenType> = phf_map! {
     "and" => TokenType::And,
+    "assert" => TokenType::Assert,
     "class" => TokenType::Class,
+    "const" => TokenType::Const,
     "else" => TokenType::Else,
+    "enum" => TokenType::Enum,
     "false" => TokenType::False,
     "for" => TokenType::For,
     "fun" => TokenType::Fun,
     "if" => TokenType::If,
+    "in" => TokenType::In,
     "nil" => TokenType::Nil,
+    "not" => TokenType::Not,
     "or" => TokenType::Or,
     "print" => TokenType::Print,
     "return" => TokenType::Return,
@@ -24,9 +33,18 @@ const KEYWORDS: phf::Map<&'static str, TokenType> = phf_map! {
     "while" => TokenType::While,
 };
 
+// a slice of the source, either a token or trivia
+pub struct Piece<'a> {
+    pub token: Option<&'a Token>,
+    pub text: &'a str,
+}
+
 pub struct Scanner {
     source: String,
+    dialect: Rc<Dialect>,
     pub tokens: Vec<Token>,
+    // the byte range in the source of each token
+    spans: Vec<Range<usize>>,
     start: usize,
     current: usize,
     line: usize,
@@ -50,9 +68,14 @@ pub enum LexicalError {
 
 impl Scanner {
     pub fn new(source: String) -> Self {
+        Self::with_dialect(source, Rc::new(Dialect::default()))
+    }
+    pub fn with_dialect(source: String, dialect: Rc<Dialect>) -> Self {
         Self {
             source,
+            dialect,
             tokens: vec![],
+            spans: vec![],
             start: 0,
             current: 0,
             line: 1,
@@ -62,7 +85,9 @@ impl Scanner {
     pub fn default() -> Self {
         Self {
             source: "".to_string(),
+            dialect: Rc::new(Dialect::default()),
             tokens: vec![],
+            spans: vec![],
             start: 0,
             current: 0,
             line: 1,
@@ -70,6 +95,24 @@ impl Scanner {
         }
     }
 
+    // the scanned source as tokens and the trivia between them, whitespace
+    // and comments, joining the text of the pieces gives back the source
+    pub fn pieces(&self) -> Vec<Piece<'_>> {
+        let mut pieces = vec![];
+        let mut end = 0;
+        for (token, span) in self.tokens.iter().zip(&self.spans) {
+            if span.start > end {
+                pieces.push(Piece { token: None, text: &self.source[end..span.start] });
+            }
+            pieces.push(Piece { token: Some(token), text: &self.source[span.clone()] });
+            end = span.end;
+        }
+        if end < self.source.len() {
+            pieces.push(Piece { token: None, text: &self.source[end..] });
+        }
+        pieces
+    }
+
     fn add_error(&mut self, e: Error) {
         self.errors.push(e);
     }
@@ -78,6 +121,10 @@ impl Scanner {
         &self.errors
     }
 
+    pub fn take_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.errors)
+    }
+
     pub fn scan_tokens(&mut self) {
         while !self.is_at_end() {
             self.start = self.current;
@@ -87,6 +134,7 @@ impl Scanner {
             }
         }
 
+        self.spans.push(self.source.len()..self.source.len());
         self.tokens.push(Token::new(
             TokenType::EOF,
             "".to_string(),
@@ -95,14 +143,17 @@ impl Scanner {
         ));
     }
 
+    // `current` is a byte offset into the source, it always moves by whole
+    // characters so slicing the source by it never splits one
     fn advance(&mut self) -> char {
-        let c = self.source.as_bytes()[self.current] as char;
-        self.current += 1;
+        let c = self.source[self.current..].chars().next().unwrap();
+        self.current += c.len_utf8();
         c
     }
 
     fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
         let text = self.source[self.start..self.current].to_string();
+        self.spans.push(self.start..self.current);
         self.tokens
             .push(Token::new(token_type, text, literal, self.line));
     }
@@ -115,8 +166,23 @@ impl Scanner {
             ')' => self.add_token(TokenType::RightParen, None),
             '{' => self.add_token(TokenType::LeftBrace, None),
             '}' => self.add_token(TokenType::RightBrace, None),
+            '[' => self.add_token(TokenType::LeftBracket, None),
+            ']' => self.add_token(TokenType::RightBracket, None),
+            ':' => self.add_token(TokenType::Colon, None),
             ',' => self.add_token(TokenType::Comma, None),
-            '.' => self.add_token(TokenType::Dot, None),
+            '.' => {
+                if self.is_next_char('.') {
+                    if self.is_next_char('.') {
+                        self.add_token(TokenType::Ellipsis, None)
+                    } else if self.is_next_char('=') {
+                        self.add_token(TokenType::DotDotEqual, None)
+                    } else {
+                        self.add_token(TokenType::DotDot, None)
+                    }
+                } else {
+                    self.add_token(TokenType::Dot, None)
+                }
+            }
             '-' => self.add_token(TokenType::Minus, None),
             '+' => self.add_token(TokenType::Plus, None),
             ';' => self.add_token(TokenType::SemiColon, None),
@@ -166,11 +232,29 @@ impl Scanner {
                     None,
                 )
             }
+            '?' => {
+                if self.is_next_char('.') {
+                    self.add_token(TokenType::QuestionDot, None)
+                } else if self.is_next_char('[') {
+                    self.add_token(TokenType::QuestionLeftBracket, None)
+                } else if self.is_next_char('?') {
+                    self.add_token(TokenType::QuestionQuestion, None)
+                } else {
+                    return Err(LexicalError::InvalidCharacter(c, self.line, self.current).into());
+                }
+            }
             '/' => {
                 if self.is_next_char('/') {
+                    // `////` and longer are ordinary comments
+                    let is_doc = (self.peek() == '/' && self.peek_next() != '/') || self.peek() == '!';
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+                    if is_doc {
+                        let text = &self.source[self.start + 3..self.current];
+                        let text = text.strip_prefix(' ').unwrap_or(text).trim_end().to_string();
+                        self.add_token(TokenType::DocComment, Some(Literal::String(text)));
+                    }
                 } else if self.is_next_char('*') {
                     self.advance();
                     let mut depth = 1;
@@ -223,24 +307,18 @@ impl Scanner {
         if self.is_at_end() {
             return false;
         }
-        if self.source.chars().nth(self.current).unwrap() != c {
+        if self.peek() != c {
             return false;
         }
 
-        self.current += 1;
+        self.current += c.len_utf8();
         true
     }
     fn peek(&self) -> char {
-        if self.is_at_end() {
-            return '\0';
-        }
-        self.source.chars().nth(self.current).unwrap()
+        self.source[self.current..].chars().next().unwrap_or('\0')
     }
     fn peek_next(&self) -> char {
-        if self.current + 1 >= self.source.len() {
-            return '\0';
-        }
-        self.source.chars().nth(self.current + 1).unwrap()
+        self.source[self.current..].chars().nth(1).unwrap_or('\0')
     }
     fn is_at_end(&self) -> bool {
         self.current >= self.source.len()
@@ -299,15 +377,7 @@ impl Scanner {
 
         let text = &self.source[self.start..self.current];
 
-        let token_type;
-        let t = KEYWORDS.get(text);
-
-        if let Some(id) = t {
-            token_type = *id;
-        }
-        else {
-            return Err(LexicalError::UnknownToken(self.line, text.to_string()).into())
-        }
+        let token_type = self.dialect.keyword(text).unwrap_or(TokenType::Identifier);
         self.add_token(token_type, None);
         Ok(())
     }
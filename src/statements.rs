@@ -1,8 +1,25 @@
-use crate::ast::Expr;
+use crate::ast::{Expr, Pattern, TypeAnnotation};
 use crate::token::Token;
 
+// one `Name` or `Name(field, ...)` entry of an enum declaration
+#[derive(Clone)]
+pub struct EnumVariant {
+    pub name: Token,
+    pub fields: Option<Vec<Token>>,
+    pub doc: Option<String>,
+}
+
+#[derive(Clone)]
 pub enum Statement {
     Expression(Expr),
     Print(Expr),
-    Var(Token, Expr)
+    // declarations end with the `///` doc comment written before them
+    Var(Pattern, Option<TypeAnnotation>, Expr, Option<String>),
+    Const(Pattern, Option<TypeAnnotation>, Expr, Option<String>),
+    Enum(Token, Vec<EnumVariant>, Option<String>),
+    // `assert condition, message;`, the keyword is kept for its line
+    Assert(Token, Expr, Option<Expr>),
+    // `test "name" { ... }`, only `brainrot test` runs the body, other runs
+    // skip it
+    Test(Token, Vec<Statement>),
 }
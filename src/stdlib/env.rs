@@ -0,0 +1,37 @@
+use super::{expect_string, invalid_argument, Module, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::token::Literal;
+use anyhow::Result;
+
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("get", 1, 1, get),
+        NativeFunction::new("set", 2, 2, set),
+    ];
+
+    let mut module = Module::new("env");
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+// the value of an environment variable, nil when it is unset or not unicode
+fn get(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let name = expect_string(&args, 0, "env.get", line)?;
+    match std::env::var(name) {
+        Ok(value) => Ok(Literal::String(value)),
+        Err(_) => Ok(Literal::Nil),
+    }
+}
+
+// sets a variable for this process and the processes it starts
+fn set(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let name = expect_string(&args, 0, "env.set", line)?;
+    let value = expect_string(&args, 1, "env.set", line)?;
+    if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+        return Err(invalid_argument(line, "env.set", format!("invalid variable '{name}'")));
+    }
+    std::env::set_var(name, value);
+    Ok(Literal::Nil)
+}
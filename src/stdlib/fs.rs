@@ -0,0 +1,99 @@
+use super::{expect_string, Module, NativeFunction};
+use crate::interpreter::{InterpretError, Interpreter};
+use crate::token::Literal;
+use anyhow::Result;
+use std::io::Write;
+
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("read_text", 1, 1, read_text),
+        NativeFunction::new("lines", 1, 1, lines),
+        NativeFunction::new("write_text", 2, 2, write_text),
+        NativeFunction::new("append", 2, 2, append),
+        NativeFunction::new("exists", 1, 1, exists),
+        NativeFunction::new("list_dir", 1, 1, list_dir),
+        NativeFunction::new("remove", 1, 1, remove),
+        NativeFunction::new("mkdir", 1, 1, mkdir),
+    ];
+
+    let mut module = Module::new("fs");
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+// turns an io error into a script error naming the function and the path
+fn io_error(line: usize, name: &str, path: &str, error: std::io::Error) -> anyhow::Error {
+    InterpretError::Io(line, format!("fs.{name}"), path.to_string(), error.to_string()).into()
+}
+
+fn read_text(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.read_text", line)?;
+    let text = std::fs::read_to_string(path).map_err(|e| io_error(line, "read_text", path, e))?;
+    Ok(Literal::String(text))
+}
+
+// the lines of a text file without their line endings
+fn lines(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.lines", line)?;
+    let text = std::fs::read_to_string(path).map_err(|e| io_error(line, "lines", path, e))?;
+    Ok(Literal::Tuple(text.lines().map(|l| Literal::String(l.to_string())).collect()))
+}
+
+fn write_text(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.write_text", line)?;
+    let text = expect_string(&args, 1, "fs.write_text", line)?;
+    std::fs::write(path, text).map_err(|e| io_error(line, "write_text", path, e))?;
+    Ok(Literal::Nil)
+}
+
+fn append(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.append", line)?;
+    let text = expect_string(&args, 1, "fs.append", line)?;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .map_err(|e| io_error(line, "append", path, e))?;
+    Ok(Literal::Nil)
+}
+
+fn exists(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.exists", line)?;
+    Ok(Literal::Boolean(std::path::Path::new(path).exists()))
+}
+
+// the sorted names of the entries in a directory
+fn list_dir(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.list_dir", line)?;
+    let mut names = std::fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+                .collect::<std::io::Result<Vec<String>>>()
+        })
+        .map_err(|e| io_error(line, "list_dir", path, e))?;
+    names.sort();
+    Ok(Literal::Tuple(names.into_iter().map(Literal::String).collect()))
+}
+
+// removes a file or an empty directory
+fn remove(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.remove", line)?;
+    let result = if std::path::Path::new(path).is_dir() {
+        std::fs::remove_dir(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    result.map_err(|e| io_error(line, "remove", path, e))?;
+    Ok(Literal::Nil)
+}
+
+// creates the directory along with any missing parents
+fn mkdir(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let path = expect_string(&args, 0, "fs.mkdir", line)?;
+    std::fs::create_dir_all(path).map_err(|e| io_error(line, "mkdir", path, e))?;
+    Ok(Literal::Nil)
+}
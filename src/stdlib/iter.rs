@@ -0,0 +1,255 @@
+use super::{invalid_argument, Module, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::map::MapValue;
+use crate::token::Literal;
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// functions over sequences, anything that can be spread: tuples, ranges and
+// maps, which give (key, value) pairs. callbacks are any callable value, like
+// natives or enum variant constructors, and results are tuples
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("map", 2, 2, map),
+        NativeFunction::new("filter", 2, 2, filter),
+        NativeFunction::new("reduce", 2, 3, reduce),
+        NativeFunction::new("sort", 1, 2, sort),
+        NativeFunction::new("reverse", 1, 1, reverse),
+        NativeFunction::new("any", 2, 2, any),
+        NativeFunction::new("all", 2, 2, all),
+        NativeFunction::new("zip", 2, usize::MAX, zip),
+        NativeFunction::new("enumerate", 1, 1, enumerate),
+        NativeFunction::new("flat_map", 2, 2, flat_map),
+        NativeFunction::new("group_by", 2, 2, group_by),
+        NativeFunction::new("unique", 1, 1, unique),
+    ];
+
+    let mut module = Module::new("iter");
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+fn expect_sequence(arguments: &[Literal], index: usize, name: &str, line: usize) -> Result<Vec<Literal>> {
+    match Interpreter::sequence(&arguments[index], line)? {
+        Some(values) => Ok(values),
+        None => Err(invalid_argument(line, name, format!("expected a tuple, range or map, got {}", arguments[index]))),
+    }
+}
+
+// calls a predicate, which has to answer with a boolean
+fn test(interpreter: &mut Interpreter, predicate: &Literal, value: Literal, name: &str, line: usize) -> Result<bool> {
+    match interpreter.call_value(predicate.clone(), vec![value], line)? {
+        Literal::Boolean(b) => Ok(b),
+        other => Err(invalid_argument(line, name, format!("predicate returned {other}, expected a boolean"))),
+    }
+}
+
+// numbers, strings, booleans and dates order among themselves, tuples order
+// element by element
+fn compare(a: &Literal, b: &Literal) -> Option<Ordering> {
+    match (a, b) {
+        (Literal::Number(a), Literal::Number(b)) => a.partial_cmp(b),
+        (Literal::String(a), Literal::String(b)) => Some(a.cmp(b)),
+        (Literal::Boolean(a), Literal::Boolean(b)) => Some(a.cmp(b)),
+        (Literal::Date(a), Literal::Date(b)) => Some(a.cmp(b)),
+        (Literal::Tuple(a), Literal::Tuple(b)) => {
+            for (a, b) in a.iter().zip(b) {
+                match compare(a, b)? {
+                    Ordering::Equal => continue,
+                    order => return Some(order),
+                }
+            }
+            Some(a.len().cmp(&b.len()))
+        }
+        _ => None,
+    }
+}
+
+// the order sort uses: the same as compare wherever that gives an answer, and
+// by kind otherwise, so the sort itself always sees a total order. keys are
+// checked for NaN before this is used, which is what keeps numbers total
+fn total_order(a: &Literal, b: &Literal) -> Ordering {
+    match (a, b) {
+        (Literal::Tuple(a), Literal::Tuple(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| total_order(a, b))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        _ => compare(a, b).unwrap_or_else(|| kind(a).cmp(&kind(b))),
+    }
+}
+
+fn kind(value: &Literal) -> u8 {
+    match value {
+        Literal::Number(_) => 0,
+        Literal::String(_) => 1,
+        Literal::Boolean(_) => 2,
+        Literal::Date(_) => 3,
+        Literal::Tuple(_) => 4,
+        _ => 5,
+    }
+}
+
+fn contains_nan(value: &Literal) -> bool {
+    match value {
+        Literal::Number(n) => n.is_nan(),
+        Literal::Tuple(values) => values.iter().any(contains_nan),
+        _ => false,
+    }
+}
+
+fn map(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let values = expect_sequence(&args, 0, "iter.map", line)?;
+    let mapped = values
+        .into_iter()
+        .map(|value| interpreter.call_value(args[1].clone(), vec![value], line))
+        .collect::<Result<_>>()?;
+    Ok(Literal::Tuple(mapped))
+}
+
+fn filter(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut kept = vec![];
+    for value in expect_sequence(&args, 0, "iter.filter", line)? {
+        if test(interpreter, &args[1], value.clone(), "iter.filter", line)? {
+            kept.push(value);
+        }
+    }
+    Ok(Literal::Tuple(kept))
+}
+
+// folds from the left, without an initial value the first element starts the
+// fold and an empty sequence is an error
+fn reduce(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut values = expect_sequence(&args, 0, "iter.reduce", line)?.into_iter();
+    let Some(mut result) = args.get(2).cloned().or_else(|| values.next()) else {
+        return Err(invalid_argument(line, "iter.reduce", "empty sequence with no initial value".to_string()));
+    };
+    for value in values {
+        result = interpreter.call_value(args[1].clone(), vec![result, value], line)?;
+    }
+    Ok(result)
+}
+
+// a stable sort, by the values themselves or by what the key function gives
+// for each of them
+fn sort(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let values = expect_sequence(&args, 0, "iter.sort", line)?;
+    let keys = match args.get(1) {
+        Some(key) => values
+            .iter()
+            .map(|value| interpreter.call_value(key.clone(), vec![value.clone()], line))
+            .collect::<Result<Vec<_>>>()?,
+        None => values.clone(),
+    };
+
+    if let Some(key) = keys.iter().find(|key| contains_nan(key)) {
+        return Err(invalid_argument(line, "iter.sort", format!("cannot order {key}")));
+    }
+
+    // once sorted by the total order, any two keys that cannot be compared
+    // leave a neighbouring pair that cannot be compared either
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| total_order(&keys[a], &keys[b]));
+    for pair in order.windows(2) {
+        let (a, b) = (&keys[pair[0]], &keys[pair[1]]);
+        if compare(a, b).is_none() {
+            return Err(invalid_argument(line, "iter.sort", format!("cannot order {a} and {b}")));
+        }
+    }
+    Ok(Literal::Tuple(order.into_iter().map(|i| values[i].clone()).collect()))
+}
+
+fn reverse(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut values = expect_sequence(&args, 0, "iter.reverse", line)?;
+    values.reverse();
+    Ok(Literal::Tuple(values))
+}
+
+fn any(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    for value in expect_sequence(&args, 0, "iter.any", line)? {
+        if test(interpreter, &args[1], value, "iter.any", line)? {
+            return Ok(Literal::Boolean(true));
+        }
+    }
+    Ok(Literal::Boolean(false))
+}
+
+fn all(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    for value in expect_sequence(&args, 0, "iter.all", line)? {
+        if !test(interpreter, &args[1], value, "iter.all", line)? {
+            return Ok(Literal::Boolean(false));
+        }
+    }
+    Ok(Literal::Boolean(true))
+}
+
+// tuples of the elements at the same position, as long as the shortest input
+fn zip(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let sequences = (0..args.len())
+        .map(|i| expect_sequence(&args, i, "iter.zip", line))
+        .collect::<Result<Vec<_>>>()?;
+    let len = sequences.iter().map(Vec::len).min().unwrap_or_default();
+    let zipped = (0..len)
+        .map(|i| Literal::Tuple(sequences.iter().map(|values| values[i].clone()).collect()))
+        .collect();
+    Ok(Literal::Tuple(zipped))
+}
+
+// (index, value) pairs
+fn enumerate(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let values = expect_sequence(&args, 0, "iter.enumerate", line)?;
+    let pairs = values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| Literal::Tuple(vec![Literal::Number(i as f32), value]))
+        .collect();
+    Ok(Literal::Tuple(pairs))
+}
+
+// maps every value to a sequence and joins the results
+fn flat_map(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut flattened = vec![];
+    for value in expect_sequence(&args, 0, "iter.flat_map", line)? {
+        let result = interpreter.call_value(args[1].clone(), vec![value], line)?;
+        flattened.extend(expect_sequence(&[result], 0, "iter.flat_map", line)?);
+    }
+    Ok(Literal::Tuple(flattened))
+}
+
+// a map from each key, as a string, to a tuple of the values with that key,
+// in the order the keys first appear
+fn group_by(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut groups: Vec<(String, Vec<Literal>)> = vec![];
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for value in expect_sequence(&args, 0, "iter.group_by", line)? {
+        let key = interpreter.call_value(args[1].clone(), vec![value.clone()], line)?.to_string();
+        match positions.get(&key) {
+            Some(&position) => groups[position].1.push(value),
+            None => {
+                positions.insert(key.clone(), groups.len());
+                groups.push((key, vec![value]));
+            }
+        }
+    }
+    let mut map = MapValue::default();
+    for (key, values) in groups {
+        map.insert(key, Literal::Tuple(values));
+    }
+    Ok(Literal::Map(Rc::new(map)))
+}
+
+// drops repeated values, keeping the first of each
+fn unique(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut kept: Vec<Literal> = vec![];
+    for value in expect_sequence(&args, 0, "iter.unique", line)? {
+        if !kept.contains(&value) {
+            kept.push(value);
+        }
+    }
+    Ok(Literal::Tuple(kept))
+}
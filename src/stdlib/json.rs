@@ -0,0 +1,405 @@
+use super::{expect_count, expect_string, invalid_argument, Module, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::map::MapValue;
+use crate::range::MAX_EXPANDED;
+use crate::token::Literal;
+use anyhow::Result;
+use std::rc::Rc;
+
+// deeper documents are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 512;
+
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("parse", 1, 1, parse),
+        NativeFunction::new("stringify", 1, 2, stringify),
+    ];
+
+    let mut module = Module::new("json");
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+// objects become maps, arrays become tuples and null becomes nil
+fn parse(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let text = expect_string(&args, 0, "json.parse", line)?;
+    let mut parser = JsonParser {
+        chars: text.chars().collect(),
+        current: 0,
+        line: 1,
+        column: 1,
+        depth: 0,
+    };
+    parser
+        .document()
+        .map_err(|message| invalid_argument(line, "json.parse", message))
+}
+
+// the widest indent stringify uses, larger ones are capped to it the way
+// JavaScript's JSON.stringify does
+const MAX_INDENT: usize = 10;
+
+// compact by default, with a second argument nested values go on their own
+// lines indented by that many spaces
+fn stringify(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let indent = match args.get(1) {
+        Some(_) => expect_count(&args, 1, "json.stringify", line)?.min(MAX_INDENT),
+        None => 0,
+    };
+    let mut out = String::new();
+    write_value(&args[0], indent, 0, &mut out)
+        .map_err(|message| invalid_argument(line, "json.stringify", message))?;
+    Ok(Literal::String(out))
+}
+
+//----------------------------------PARSING-----------------------------------
+
+struct JsonParser {
+    chars: Vec<char>,
+    current: usize,
+    // position of the next character, both count from 1
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+impl JsonParser {
+    fn document(&mut self) -> ParseResult<Literal> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return Err(self.unexpected());
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> ParseResult<Literal> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.nested(Self::object),
+            Some('[') => self.nested(Self::array),
+            Some('"') => Ok(Literal::String(self.string()?)),
+            Some('t') => self.keyword("true", Literal::Boolean(true)),
+            Some('f') => self.keyword("false", Literal::Boolean(false)),
+            Some('n') => self.keyword("null", Literal::Nil),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> ParseResult<Literal>) -> ParseResult<Literal> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("nesting deeper than {MAX_DEPTH} levels")));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> ParseResult<Literal> {
+        self.advance();
+        let mut map = MapValue::default();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.advance();
+            return Ok(Literal::Map(Rc::new(map)));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.unexpected());
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.value()?;
+            map.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.advance(),
+                Some('}') => {
+                    self.advance();
+                    return Ok(Literal::Map(Rc::new(map)));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> ParseResult<Literal> {
+        self.advance();
+        let mut values = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.advance();
+            return Ok(Literal::Tuple(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.advance(),
+                Some(']') => {
+                    self.advance();
+                    return Ok(Literal::Tuple(values));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        self.advance();
+        let mut s = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string".to_string())),
+                Some('"') => {
+                    self.advance();
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    self.advance();
+                    let escaped = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            self.advance();
+                            s.push(self.unicode_escape()?);
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape sequence".to_string())),
+                    };
+                    self.advance();
+                    s.push(escaped);
+                }
+                Some(c) if (c as u32) < 0x20 => return Err(self.error("control character in string".to_string())),
+                Some(c) => {
+                    self.advance();
+                    s.push(c);
+                }
+            }
+        }
+    }
+
+    // the four hex digits after `\u`, joining a surrogate pair into one
+    // character
+    fn unicode_escape(&mut self) -> ParseResult<char> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("invalid unicode escape".to_string()));
+        }
+        if self.peek() != Some('\\') || self.chars.get(self.current + 1) != Some(&'u') {
+            return Err(self.error("unpaired surrogate in unicode escape".to_string()));
+        }
+        self.advance();
+        self.advance();
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("unpaired surrogate in unicode escape".to_string()));
+        }
+        let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape".to_string()))
+    }
+
+    fn hex4(&mut self) -> ParseResult<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) else {
+                return Err(self.error("invalid unicode escape".to_string()));
+            };
+            self.advance();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> ParseResult<Literal> {
+        let start = self.current;
+        let (line, column) = (self.line, self.column);
+        if self.peek() == Some('-') {
+            self.advance();
+        }
+        match self.peek() {
+            Some('0') => self.advance(),
+            Some(c) if c.is_ascii_digit() => self.digits(),
+            _ => return Err(self.unexpected()),
+        }
+        if self.peek() == Some('.') {
+            self.advance();
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.unexpected());
+            }
+            self.digits();
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.advance();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.advance();
+            }
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.unexpected());
+            }
+            self.digits();
+        }
+        let text: String = self.chars[start..self.current].iter().collect();
+        match text.parse() {
+            Ok(n) => Ok(Literal::Number(n)),
+            Err(_) => Err(format!("invalid number '{text}' at line {line}, column {column}")),
+        }
+    }
+
+    fn digits(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Literal) -> ParseResult<Literal> {
+        for expected in word.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.unexpected());
+            }
+            self.advance();
+        }
+        Ok(value)
+    }
+
+    fn expect(&mut self, expected: char) -> ParseResult<()> {
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected());
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.advance();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.current).copied()
+    }
+
+    fn advance(&mut self) {
+        if self.peek() == Some('\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        self.current += 1;
+    }
+
+    fn unexpected(&self) -> String {
+        match self.peek() {
+            Some(c) => self.error(format!("unexpected character '{}'", c.escape_default())),
+            None => self.error("unexpected end of input".to_string()),
+        }
+    }
+
+    fn error(&self, message: String) -> String {
+        format!("{message} at line {}, column {}", self.line, self.column)
+    }
+}
+
+//--------------------------------STRINGIFYING--------------------------------
+
+fn write_value(value: &Literal, indent: usize, depth: usize, out: &mut String) -> std::result::Result<(), String> {
+    match value {
+        Literal::Nil => out.push_str("null"),
+        Literal::Boolean(b) => out.push_str(&b.to_string()),
+        Literal::Number(n) if n.is_finite() => out.push_str(&n.to_string()),
+        Literal::String(s) => write_string(s, out),
+        // dates are written the way they print, as ISO-8601 strings
+        Literal::Date(date) => write_string(&date.to_string(), out),
+        Literal::Tuple(values) => {
+            let values: Vec<&Literal> = values.iter().collect();
+            write_sequence(&values, '[', ']', indent, depth, out, |value, out| {
+                write_value(value, indent, depth + 1, out)
+            })?
+        }
+        Literal::Range(range) if range.len() > MAX_EXPANDED => {
+            return Err(format!("cannot convert {range} to JSON, it has more than {MAX_EXPANDED} elements"));
+        }
+        Literal::Range(range) => {
+            let values: Vec<Literal> = range.iter().map(Literal::Number).collect();
+            write_value(&Literal::Tuple(values), indent, depth, out)?
+        }
+        Literal::Map(map) => {
+            let entries: Vec<&(String, Literal)> = map.entries().iter().collect();
+            write_sequence(&entries, '{', '}', indent, depth, out, |(key, value), out| {
+                write_string(key, out);
+                out.push_str(if indent > 0 { ": " } else { ":" });
+                write_value(value, indent, depth + 1, out)
+            })?
+        }
+        other => return Err(format!("cannot convert {other} to JSON")),
+    }
+    Ok(())
+}
+
+fn write_sequence<T>(
+    items: &[T],
+    open: char,
+    close: char,
+    indent: usize,
+    depth: usize,
+    out: &mut String,
+    mut write_item: impl FnMut(&T, &mut String) -> std::result::Result<(), String>,
+) -> std::result::Result<(), String> {
+    out.push(open);
+    if items.is_empty() {
+        out.push(close);
+        return Ok(());
+    }
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if indent > 0 {
+            out.push('\n');
+            out.push_str(&" ".repeat(indentation(indent, depth + 1)?));
+        }
+        write_item(item, out)?;
+    }
+    if indent > 0 {
+        out.push('\n');
+        out.push_str(&" ".repeat(indentation(indent, depth)?));
+    }
+    out.push(close);
+    Ok(())
+}
+
+fn indentation(indent: usize, depth: usize) -> std::result::Result<usize, String> {
+    indent.checked_mul(depth).ok_or_else(|| "values are nested too deeply".to_string())
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,187 @@
+use super::{expect_number, expect_tuple, invalid_argument, Module, NativeFunction};
+use crate::interpreter::{InterpretError, Interpreter};
+use crate::token::Literal;
+use anyhow::Result;
+
+// functions that can leave their domain also come in a `_or_nan` variant
+// which returns nan instead of raising an error
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("sqrt", 1, 1, sqrt),
+        NativeFunction::new("sqrt_or_nan", 1, 1, sqrt_or_nan),
+        NativeFunction::new("pow", 2, 2, pow),
+        NativeFunction::new("pow_or_nan", 2, 2, pow_or_nan),
+        NativeFunction::new("abs", 1, 1, abs),
+        NativeFunction::new("floor", 1, 1, floor),
+        NativeFunction::new("ceil", 1, 1, ceil),
+        NativeFunction::new("round", 1, 1, round),
+        NativeFunction::new("min", 1, usize::MAX, min),
+        NativeFunction::new("max", 1, usize::MAX, max),
+        NativeFunction::new("sin", 1, 1, sin),
+        NativeFunction::new("cos", 1, 1, cos),
+        NativeFunction::new("tan", 1, 1, tan),
+        NativeFunction::new("asin", 1, 1, asin),
+        NativeFunction::new("asin_or_nan", 1, 1, asin_or_nan),
+        NativeFunction::new("acos", 1, 1, acos),
+        NativeFunction::new("acos_or_nan", 1, 1, acos_or_nan),
+        NativeFunction::new("atan", 1, 1, atan),
+        NativeFunction::new("atan2", 2, 2, atan2),
+        NativeFunction::new("log", 1, 2, log),
+        NativeFunction::new("log_or_nan", 1, 2, log_or_nan),
+        NativeFunction::new("exp", 1, 1, exp),
+        NativeFunction::new("is_nan", 1, 1, is_nan),
+        NativeFunction::new("clamp", 3, 3, clamp),
+    ];
+
+    let mut module = Module::new("math");
+    module.add("pi", Literal::Number(std::f32::consts::PI));
+    module.add("e", Literal::Number(std::f32::consts::E));
+    module.add("inf", Literal::Number(f32::INFINITY));
+    module.add("nan", Literal::Number(f32::NAN));
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+// applies `f` to the number arguments, a nan result from arguments that are
+// not nan themselves is a domain error unless `allow_nan` is set
+fn apply(args: &[Literal], name: &str, line: usize, allow_nan: bool, f: fn(&[f32]) -> f32) -> Result<Literal> {
+    let numbers = (0..args.len())
+        .map(|i| expect_number(args, i, name, line))
+        .collect::<Result<Vec<f32>>>()?;
+    let result = f(&numbers);
+    if result.is_nan() && !allow_nan && !numbers.iter().any(|n| n.is_nan()) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let error = InterpretError::DomainError(line, name.to_string(), args.join(", "));
+        return Err(error.into());
+    }
+    Ok(Literal::Number(result))
+}
+
+fn sqrt(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "sqrt", line, false, |n| n[0].sqrt())
+}
+
+fn sqrt_or_nan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "sqrt_or_nan", line, true, |n| n[0].sqrt())
+}
+
+fn pow(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "pow", line, false, |n| n[0].powf(n[1]))
+}
+
+fn pow_or_nan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "pow_or_nan", line, true, |n| n[0].powf(n[1]))
+}
+
+fn abs(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "abs", line, false, |n| n[0].abs())
+}
+
+fn floor(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "floor", line, false, |n| n[0].floor())
+}
+
+fn ceil(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "ceil", line, false, |n| n[0].ceil())
+}
+
+fn round(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "round", line, false, |n| n[0].round())
+}
+
+// min(a, b, ...) or min(tuple)
+fn extreme(args: Vec<Literal>, name: &str, line: usize, f: fn(&[f32]) -> f32) -> Result<Literal> {
+    let args = match args.as_slice() {
+        [Literal::Tuple(_)] => expect_tuple(&args, 0, name, line)?.to_vec(),
+        _ => args,
+    };
+    if args.is_empty() {
+        return Err(invalid_argument(line, name, "expected at least one number".to_string()));
+    }
+    apply(&args, name, line, false, f)
+}
+
+fn min(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    extreme(args, "min", line, |n| n.iter().copied().fold(f32::INFINITY, f32::min))
+}
+
+fn max(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    extreme(args, "max", line, |n| n.iter().copied().fold(f32::NEG_INFINITY, f32::max))
+}
+
+fn sin(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "sin", line, false, |n| n[0].sin())
+}
+
+fn cos(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "cos", line, false, |n| n[0].cos())
+}
+
+fn tan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "tan", line, false, |n| n[0].tan())
+}
+
+fn asin(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "asin", line, false, |n| n[0].asin())
+}
+
+fn asin_or_nan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "asin_or_nan", line, true, |n| n[0].asin())
+}
+
+fn acos(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "acos", line, false, |n| n[0].acos())
+}
+
+fn acos_or_nan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "acos_or_nan", line, true, |n| n[0].acos())
+}
+
+fn atan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "atan", line, false, |n| n[0].atan())
+}
+
+fn atan2(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "atan2", line, false, |n| n[0].atan2(n[1]))
+}
+
+// the natural logarithm, or the logarithm to the base given as the second
+// argument
+fn logarithm(n: &[f32]) -> f32 {
+    match n {
+        [x] => x.ln(),
+        [x, base] => x.log(*base),
+        _ => unreachable!(),
+    }
+}
+
+fn log(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "log", line, false, logarithm)
+}
+
+fn log_or_nan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "log_or_nan", line, true, logarithm)
+}
+
+fn exp(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    apply(&args, "exp", line, false, |n| n[0].exp())
+}
+
+fn is_nan(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    Ok(Literal::Boolean(expect_number(&args, 0, "is_nan", line)?.is_nan()))
+}
+
+fn clamp(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let low = expect_number(&args, 1, "clamp", line)?;
+    let high = expect_number(&args, 2, "clamp", line)?;
+    // f32::clamp panics on a NaN bound, a NaN value just comes back as NaN
+    if low.is_nan() || high.is_nan() {
+        return Err(invalid_argument(line, "clamp", format!("bounds cannot be NaN, got {low} and {high}")));
+    }
+    if low > high {
+        return Err(invalid_argument(line, "clamp", format!("lower bound {low} is above upper bound {high}")));
+    }
+    apply(&args, "clamp", line, false, |n| n[0].clamp(n[1], n[2]))
+}
@@ -0,0 +1,175 @@
+pub mod env;
+pub mod fs;
+pub mod iter;
+pub mod json;
+pub mod math;
+pub mod process;
+pub mod random;
+pub mod regex;
+pub mod strings;
+pub mod sys;
+pub mod time;
+
+use crate::interpreter::{InterpretError, Interpreter};
+use crate::token::Literal;
+use anyhow::Result;
+use std::rc::Rc;
+
+// the modules every program starts with, bound as constant globals
+pub fn globals() -> Vec<(&'static str, Literal)> {
+    vec![
+        ("math", Literal::Module(Rc::new(math::module()))),
+        ("fs", Literal::Module(Rc::new(fs::module()))),
+        ("random", Literal::Module(Rc::new(random::module()))),
+        ("time", Literal::Module(Rc::new(time::module()))),
+        ("json", Literal::Module(Rc::new(json::module()))),
+        ("regex", Literal::Module(Rc::new(regex::module()))),
+        ("iter", Literal::Module(Rc::new(iter::module()))),
+        ("sys", Literal::Module(Rc::new(sys::module(&[])))),
+        ("env", Literal::Module(Rc::new(env::module()))),
+        ("process", Literal::Module(Rc::new(process::module()))),
+    ]
+}
+
+// a namespace of natives and constants reached through dot access, like
+// math.sqrt or math.pi
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub name: &'static str,
+    members: Vec<(&'static str, Literal)>,
+}
+
+impl Module {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            members: vec![],
+        }
+    }
+
+    pub fn add(&mut self, name: &'static str, value: Literal) {
+        self.members.push((name, value));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Literal> {
+        self.members.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+}
+
+impl std::fmt::Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<module {}>", self.name)
+    }
+}
+
+// receives the interpreter, the arguments (the bound receiver first for
+// methods) and the line of the call for error messages
+pub type NativeFn = fn(&mut Interpreter, Vec<Literal>, usize) -> Result<Literal>;
+
+// a function implemented in Rust, methods like "abc".upper are natives with a
+// bound receiver
+#[derive(Debug, Clone)]
+pub struct NativeFunction {
+    pub name: &'static str,
+    pub min_arity: usize,
+    pub max_arity: usize,
+    pub receiver: Option<Box<Literal>>,
+    pub function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn new(name: &'static str, min_arity: usize, max_arity: usize, function: NativeFn) -> Self {
+        Self {
+            name,
+            min_arity,
+            max_arity,
+            receiver: None,
+            function,
+        }
+    }
+
+    pub fn bind(mut self, receiver: Literal) -> Self {
+        self.receiver = Some(Box::new(receiver));
+        self
+    }
+
+    pub fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Literal>, line: usize) -> Result<Literal> {
+        if arguments.len() < self.min_arity || arguments.len() > self.max_arity {
+            let expected = if self.min_arity == self.max_arity {
+                self.min_arity.to_string()
+            } else if self.max_arity == usize::MAX {
+                format!("at least {}", self.min_arity)
+            } else {
+                format!("{} to {}", self.min_arity, self.max_arity)
+            };
+            let error = InterpretError::WrongArgumentCount(line, self.name.to_string(), expected, arguments.len());
+            return Err(error.into());
+        }
+
+        let arguments = match &self.receiver {
+            Some(receiver) => std::iter::once(*receiver.clone()).chain(arguments).collect(),
+            None => arguments,
+        };
+        (self.function)(interpreter, arguments, line)
+    }
+}
+
+// natives are equal when they are the same function bound to equal receivers
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.receiver == other.receiver
+    }
+}
+
+impl std::fmt::Display for NativeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
+
+//------------------------------ARGUMENT HELPERS------------------------------
+
+pub fn invalid_argument(line: usize, name: &str, message: String) -> anyhow::Error {
+    InterpretError::InvalidArgument(line, name.to_string(), message).into()
+}
+
+pub fn expect_number(arguments: &[Literal], index: usize, name: &str, line: usize) -> Result<f32> {
+    match &arguments[index] {
+        Literal::Number(n) => Ok(*n),
+        other => Err(invalid_argument(line, name, format!("expected a number, got {other}"))),
+    }
+}
+
+pub fn expect_string<'a>(arguments: &'a [Literal], index: usize, name: &str, line: usize) -> Result<&'a str> {
+    match &arguments[index] {
+        Literal::String(s) => Ok(s),
+        other => Err(invalid_argument(line, name, format!("expected a string, got {other}"))),
+    }
+}
+
+pub fn expect_integer(arguments: &[Literal], index: usize, name: &str, line: usize) -> Result<i64> {
+    let n = expect_number(arguments, index, name, line)?;
+    if n.fract() != 0.0 {
+        return Err(invalid_argument(line, name, format!("expected a whole number, got {n}")));
+    }
+    // the cast would saturate, i64::MAX as f32 rounds up to 2^63
+    if n < i64::MIN as f32 || n >= i64::MAX as f32 {
+        return Err(invalid_argument(line, name, format!("{n} is out of range")));
+    }
+    Ok(n as i64)
+}
+
+// a non-negative whole number, used for counts, widths and positions
+pub fn expect_count(arguments: &[Literal], index: usize, name: &str, line: usize) -> Result<usize> {
+    match &arguments[index] {
+        Literal::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        other => Err(invalid_argument(line, name, format!("expected a non-negative whole number, got {other}"))),
+    }
+}
+
+pub fn expect_tuple<'a>(arguments: &'a [Literal], index: usize, name: &str, line: usize) -> Result<&'a [Literal]> {
+    match &arguments[index] {
+        Literal::Tuple(values) => Ok(values),
+        other => Err(invalid_argument(line, name, format!("expected a tuple, got {other}"))),
+    }
+}
@@ -0,0 +1,45 @@
+use super::{expect_string, expect_tuple, invalid_argument, Module, NativeFunction};
+use crate::interpreter::{InterpretError, Interpreter};
+use crate::map::MapValue;
+use crate::token::Literal;
+use anyhow::Result;
+use std::process::Command;
+use std::rc::Rc;
+
+pub fn module() -> Module {
+    let mut module = Module::new("process");
+    module.add("run", Literal::Native(NativeFunction::new("run", 1, 2, run)));
+    module
+}
+
+// runs a program with a tuple of arguments, without a shell, and waits for
+// it, returns a map of its stdout, stderr and exit status, the status is nil
+// when the program was ended by a signal
+fn run(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let program = expect_string(&args, 0, "process.run", line)?;
+    let arguments = match args.get(1) {
+        Some(_) => expect_tuple(&args, 1, "process.run", line)?
+            .iter()
+            .map(|arg| match arg {
+                Literal::String(s) => Ok(s.clone()),
+                other => Err(invalid_argument(line, "process.run", format!("arguments must be strings, got {other}"))),
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => vec![],
+    };
+
+    let output = match Command::new(program).args(&arguments).output() {
+        Ok(output) => output,
+        Err(e) => return Err(InterpretError::Io(line, "process.run".to_string(), program.to_string(), e.to_string()).into()),
+    };
+    let status = match output.status.code() {
+        Some(code) => Literal::Number(code as f32),
+        None => Literal::Nil,
+    };
+
+    let mut result = MapValue::default();
+    result.insert("stdout".to_string(), Literal::String(String::from_utf8_lossy(&output.stdout).into_owned()));
+    result.insert("stderr".to_string(), Literal::String(String::from_utf8_lossy(&output.stderr).into_owned()));
+    result.insert("status".to_string(), status);
+    Ok(Literal::Map(Rc::new(result)))
+}
@@ -0,0 +1,151 @@
+use super::{expect_count, expect_integer, expect_tuple, invalid_argument, Module, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::token::Literal;
+use anyhow::Result;
+
+// xoshiro256** seeded through splitmix64, implemented here instead of taken
+// from a crate so a seed gives the same sequence on every platform and in
+// every version
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: [0; 4] };
+        rng.seed(seed);
+        rng
+    }
+
+    // seeded from the clock, used until the script or --seed picks a seed
+    pub fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::new(nanos)
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        let mut x = seed;
+        for slot in self.state.iter_mut() {
+            x = x.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *slot = z ^ (z >> 31);
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    // uniform in 0..bound, rejecting the values that would bias the modulo
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % bound;
+            }
+        }
+    }
+
+    // uniform in [0, 1) with the 24 bits of precision an f32 has
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            values.swap(i, j);
+        }
+    }
+}
+
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("seed", 1, 1, seed),
+        NativeFunction::new("int", 2, 2, int),
+        NativeFunction::new("float", 0, 0, float),
+        NativeFunction::new("choice", 1, 1, choice),
+        NativeFunction::new("shuffle", 1, 1, shuffle),
+        NativeFunction::new("sample", 2, 2, sample),
+    ];
+
+    let mut module = Module::new("random");
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+fn seed(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let seed = expect_integer(&args, 0, "random.seed", line)?;
+    interpreter.random.seed(seed as u64);
+    Ok(Literal::Nil)
+}
+
+// a whole number between a and b, both included
+fn int(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let low = expect_integer(&args, 0, "random.int", line)?;
+    let high = expect_integer(&args, 1, "random.int", line)?;
+    if low > high {
+        return Err(invalid_argument(line, "random.int", format!("lower bound {low} is above upper bound {high}")));
+    }
+    // the span of the full i64 range is one more than a u64 holds, and every
+    // u64 is then a fair offset
+    let span = high as i128 - low as i128 + 1;
+    let offset = match u64::try_from(span) {
+        Ok(span) => interpreter.random.below(span),
+        Err(_) => interpreter.random.next_u64(),
+    };
+    Ok(Literal::Number((low as i128 + offset as i128) as f32))
+}
+
+fn float(interpreter: &mut Interpreter, _: Vec<Literal>, _: usize) -> Result<Literal> {
+    Ok(Literal::Number(interpreter.random.next_f32()))
+}
+
+fn choice(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let values = expect_tuple(&args, 0, "random.choice", line)?;
+    if values.is_empty() {
+        return Err(invalid_argument(line, "random.choice", "cannot choose from an empty tuple".to_string()));
+    }
+    let index = interpreter.random.below(values.len() as u64) as usize;
+    Ok(values[index].clone())
+}
+
+// tuples are immutable, so this returns a shuffled copy
+fn shuffle(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut values = expect_tuple(&args, 0, "random.shuffle", line)?.to_vec();
+    interpreter.random.shuffle(&mut values);
+    Ok(Literal::Tuple(values))
+}
+
+// `count` distinct elements of the tuple in random order
+fn sample(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut values = expect_tuple(&args, 0, "random.sample", line)?.to_vec();
+    let count = expect_count(&args, 1, "random.sample", line)?;
+    if count > values.len() {
+        let message = format!("cannot take {count} values from a tuple of {}", values.len());
+        return Err(invalid_argument(line, "random.sample", message));
+    }
+    for i in 0..count {
+        let j = i + interpreter.random.below((values.len() - i) as u64) as usize;
+        values.swap(i, j);
+    }
+    values.truncate(count);
+    Ok(Literal::Tuple(values))
+}
@@ -0,0 +1,143 @@
+use super::{expect_string, invalid_argument, Module, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::map::MapValue;
+use crate::token::Literal;
+use anyhow::Result;
+use std::rc::Rc;
+
+// a compiled pattern, compiling once with regex.compile and keeping the value
+// avoids recompiling the pattern on every call
+#[derive(Debug, Clone)]
+pub struct RegexValue(pub ::regex::Regex);
+
+impl PartialEq for RegexValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl std::fmt::Display for RegexValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<regex {}>", self.0.as_str())
+    }
+}
+
+// every function takes the pattern first, either compiled or as a string,
+// so they double as the methods of compiled patterns
+pub fn module() -> Module {
+    let mut module = Module::new("regex");
+    module.add("compile", Literal::Native(NativeFunction::new("compile", 1, 1, compile)));
+    for name in ["is_match", "match", "find_all", "replace", "split"] {
+        let native = function(name).expect("regex function");
+        module.add(native.name, Literal::Native(native));
+    }
+    module
+}
+
+// the method called `name` bound to the compiled pattern `re`
+pub fn method(re: &RegexValue, name: &str) -> Option<NativeFunction> {
+    function(name).map(|mut native| {
+        // the bound pattern stands in for the first argument
+        native.min_arity -= 1;
+        native.max_arity -= 1;
+        native.bind(Literal::Regex(re.clone()))
+    })
+}
+
+fn function(name: &str) -> Option<NativeFunction> {
+    let native = match name {
+        "is_match" => NativeFunction::new("is_match", 2, 2, is_match),
+        "match" => NativeFunction::new("match", 2, 2, find),
+        "find_all" => NativeFunction::new("find_all", 2, 2, find_all),
+        "replace" => NativeFunction::new("replace", 3, 3, replace),
+        "split" => NativeFunction::new("split", 2, 2, split),
+        _ => return None,
+    };
+    Some(native)
+}
+
+fn expect_regex(arguments: &[Literal], index: usize, name: &str, line: usize) -> Result<RegexValue> {
+    match &arguments[index] {
+        Literal::Regex(re) => Ok(re.clone()),
+        Literal::String(pattern) => build(pattern, name, line),
+        other => Err(invalid_argument(line, name, format!("expected a pattern, got {other}"))),
+    }
+}
+
+fn build(pattern: &str, name: &str, line: usize) -> Result<RegexValue> {
+    match ::regex::Regex::new(pattern) {
+        Ok(re) => Ok(RegexValue(re)),
+        Err(e) => Err(invalid_argument(line, name, format!("invalid pattern: {e}"))),
+    }
+}
+
+// byte offsets into `s` as character positions, matching the string methods
+fn position(s: &str, byte: usize) -> Literal {
+    Literal::Number(s[..byte].chars().count() as f32)
+}
+
+fn compile(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let pattern = expect_string(&args, 0, "regex.compile", line)?;
+    Ok(Literal::Regex(build(pattern, "regex.compile", line)?))
+}
+
+fn is_match(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let re = expect_regex(&args, 0, "regex.is_match", line)?;
+    let s = expect_string(&args, 1, "regex.is_match", line)?;
+    Ok(Literal::Boolean(re.0.is_match(s)))
+}
+
+// the first match as a map of its text, start and end, the numbered groups as
+// a tuple and the named groups as a map, groups that took no part are nil,
+// nil when nothing matches
+fn find(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let re = expect_regex(&args, 0, "regex.match", line)?;
+    let s = expect_string(&args, 1, "regex.match", line)?;
+    let Some(captures) = re.0.captures(s) else {
+        return Ok(Literal::Nil);
+    };
+
+    let text = |i: usize| match captures.get(i) {
+        Some(group) => Literal::String(group.as_str().to_string()),
+        None => Literal::Nil,
+    };
+    let whole = captures.get(0).expect("group 0 is the whole match");
+    let groups = (1..captures.len()).map(text).collect();
+    let mut named = MapValue::default();
+    for (i, name) in re.0.capture_names().enumerate() {
+        if let Some(name) = name {
+            named.insert(name.to_string(), text(i));
+        }
+    }
+
+    let mut result = MapValue::default();
+    result.insert("text".to_string(), text(0));
+    result.insert("start".to_string(), position(s, whole.start()));
+    result.insert("end".to_string(), position(s, whole.end()));
+    result.insert("groups".to_string(), Literal::Tuple(groups));
+    result.insert("named".to_string(), Literal::Map(Rc::new(named)));
+    Ok(Literal::Map(Rc::new(result)))
+}
+
+fn find_all(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let re = expect_regex(&args, 0, "regex.find_all", line)?;
+    let s = expect_string(&args, 1, "regex.find_all", line)?;
+    let matches = re.0.find_iter(s).map(|m| Literal::String(m.as_str().to_string()));
+    Ok(Literal::Tuple(matches.collect()))
+}
+
+// replaces every match, `$1` or `${name}` in the replacement stand for a group
+// and `$$` for a dollar sign
+fn replace(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let re = expect_regex(&args, 0, "regex.replace", line)?;
+    let s = expect_string(&args, 1, "regex.replace", line)?;
+    let replacement = expect_string(&args, 2, "regex.replace", line)?;
+    Ok(Literal::String(re.0.replace_all(s, replacement).into_owned()))
+}
+
+fn split(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let re = expect_regex(&args, 0, "regex.split", line)?;
+    let s = expect_string(&args, 1, "regex.split", line)?;
+    let parts = re.0.split(s).map(|part| Literal::String(part.to_string()));
+    Ok(Literal::Tuple(parts.collect()))
+}
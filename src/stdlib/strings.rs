@@ -0,0 +1,154 @@
+use super::{expect_count, expect_string, expect_tuple, invalid_argument, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::token::Literal;
+use anyhow::Result;
+
+// the method called `name` bound to the string `s`, lengths and positions
+// count unicode scalar values rather than bytes
+pub fn method(s: &str, name: &str) -> Option<NativeFunction> {
+    let native = match name {
+        "len" => NativeFunction::new("len", 0, 0, len),
+        "upper" => NativeFunction::new("upper", 0, 0, upper),
+        "lower" => NativeFunction::new("lower", 0, 0, lower),
+        "trim" => NativeFunction::new("trim", 0, 0, trim),
+        "split" => NativeFunction::new("split", 0, 1, split),
+        "join" => NativeFunction::new("join", 1, 1, join),
+        "replace" => NativeFunction::new("replace", 2, 2, replace),
+        "starts_with" => NativeFunction::new("starts_with", 1, 1, starts_with),
+        "ends_with" => NativeFunction::new("ends_with", 1, 1, ends_with),
+        "find" => NativeFunction::new("find", 1, 1, find),
+        "chars" => NativeFunction::new("chars", 0, 0, chars),
+        "pad_left" => NativeFunction::new("pad_left", 1, 2, pad_left),
+        "pad_right" => NativeFunction::new("pad_right", 1, 2, pad_right),
+        "repeat" => NativeFunction::new("repeat", 1, 1, repeat),
+        _ => return None,
+    };
+    Some(native.bind(Literal::String(s.to_string())))
+}
+
+// the longest string, in bytes, that repeat and padding will build
+const MAX_LENGTH: usize = 1 << 24;
+
+// None is a length that does not even fit in a usize
+fn check_length(length: Option<usize>, name: &str, line: usize) -> Result<()> {
+    match length {
+        Some(length) if length <= MAX_LENGTH => Ok(()),
+        _ => Err(invalid_argument(line, name, format!("the result would be longer than {MAX_LENGTH} bytes"))),
+    }
+}
+
+fn string(s: impl Into<String>) -> Literal {
+    Literal::String(s.into())
+}
+
+fn strings<'a>(parts: impl Iterator<Item = &'a str>) -> Literal {
+    Literal::Tuple(parts.map(string).collect())
+}
+
+fn len(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "len", line)?;
+    Ok(Literal::Number(s.chars().count() as f32))
+}
+
+fn upper(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    Ok(string(expect_string(&args, 0, "upper", line)?.to_uppercase()))
+}
+
+fn lower(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    Ok(string(expect_string(&args, 0, "lower", line)?.to_lowercase()))
+}
+
+fn trim(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    Ok(string(expect_string(&args, 0, "trim", line)?.trim()))
+}
+
+// without a separator splits on whitespace, an empty separator splits into
+// single characters
+fn split(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "split", line)?;
+    if args.len() == 1 {
+        return Ok(strings(s.split_whitespace()));
+    }
+    let separator = expect_string(&args, 1, "split", line)?;
+    if separator.is_empty() {
+        return Ok(Literal::Tuple(s.chars().map(string).collect()));
+    }
+    Ok(strings(s.split(separator)))
+}
+
+// ", ".join(("a", "b")), the string is the separator
+fn join(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let separator = expect_string(&args, 0, "join", line)?;
+    let parts: Vec<String> = expect_tuple(&args, 1, "join", line)?.iter().map(|p| p.to_string()).collect();
+    Ok(string(parts.join(separator)))
+}
+
+fn replace(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "replace", line)?;
+    let from = expect_string(&args, 1, "replace", line)?;
+    let to = expect_string(&args, 2, "replace", line)?;
+    Ok(string(s.replace(from, to)))
+}
+
+fn starts_with(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "starts_with", line)?;
+    Ok(Literal::Boolean(s.starts_with(expect_string(&args, 1, "starts_with", line)?)))
+}
+
+fn ends_with(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "ends_with", line)?;
+    Ok(Literal::Boolean(s.ends_with(expect_string(&args, 1, "ends_with", line)?)))
+}
+
+// the character position of the first match or nil
+fn find(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "find", line)?;
+    let needle = expect_string(&args, 1, "find", line)?;
+    Ok(match s.find(needle) {
+        Some(byte) => Literal::Number(s[..byte].chars().count() as f32),
+        None => Literal::Nil,
+    })
+}
+
+fn chars(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "chars", line)?;
+    Ok(Literal::Tuple(s.chars().map(string).collect()))
+}
+
+// the padding to add in front or behind to reach `width`, filled with the
+// optional single character fill (a space by default)
+fn padding(args: &[Literal], name: &str, line: usize) -> Result<String> {
+    let s = expect_string(args, 0, name, line)?;
+    let width = expect_count(args, 1, name, line)?;
+    let fill = match args.get(2) {
+        Some(_) => {
+            let fill = expect_string(args, 2, name, line)?;
+            let mut chars = fill.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(invalid_argument(line, name, format!("fill must be a single character, got {fill}"))),
+            }
+        }
+        None => ' ',
+    };
+    let missing = width.saturating_sub(s.chars().count());
+    check_length(missing.checked_mul(fill.len_utf8()).and_then(|n| n.checked_add(s.len())), name, line)?;
+    Ok(std::iter::repeat_n(fill, missing).collect())
+}
+
+fn pad_left(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let padding = padding(&args, "pad_left", line)?;
+    Ok(string(padding + expect_string(&args, 0, "pad_left", line)?))
+}
+
+fn pad_right(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let padding = padding(&args, "pad_right", line)?;
+    Ok(string(expect_string(&args, 0, "pad_right", line)?.to_string() + &padding))
+}
+
+fn repeat(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "repeat", line)?;
+    let count = expect_count(&args, 1, "repeat", line)?;
+    check_length(s.len().checked_mul(count), "repeat", line)?;
+    Ok(string(s.repeat(count)))
+}
@@ -0,0 +1,23 @@
+use super::{expect_integer, Module, NativeFunction};
+use crate::interpreter::{InterpretError, Interpreter};
+use crate::token::Literal;
+use anyhow::Result;
+
+// `args` holds the command line arguments given after the script path
+pub fn module(args: &[String]) -> Module {
+    let mut module = Module::new("sys");
+    let args = args.iter().map(|arg| Literal::String(arg.clone())).collect();
+    module.add("args", Literal::Tuple(args));
+    module.add("exit", Literal::Native(NativeFunction::new("exit", 0, 1, exit)));
+    module
+}
+
+// unwinds out of the script with an Exit error, the host decides what exiting
+// means, the command line ends the process with the code
+fn exit(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let code = match args.first() {
+        Some(_) => expect_integer(&args, 0, "sys.exit", line)?,
+        None => 0,
+    };
+    Err(InterpretError::Exit(line, code as i32).into())
+}
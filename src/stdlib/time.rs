@@ -0,0 +1,367 @@
+use super::{expect_integer, expect_number, expect_string, invalid_argument, Module, NativeFunction};
+use crate::interpreter::Interpreter;
+use crate::token::Literal;
+use anyhow::Result;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+// keeps the day arithmetic in from_fields far away from overflowing
+const MAX_YEAR: i64 = 1_000_000;
+
+// where the time module gets the current time from, the interpreter holds one
+// so a host can swap in a FakeClock to make time-dependent scripts repeatable
+pub trait Clock {
+    // time since some fixed point, only differences between readings mean
+    // anything
+    fn monotonic(&self) -> Duration;
+    fn wall(&self) -> DateValue;
+    fn sleep(&mut self, duration: Duration);
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn monotonic(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn wall(&self) -> DateValue {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        DateValue { millis: since_epoch.as_millis() as i64 }
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+// a clock frozen at `start` that only moves when the script sleeps, sleeping
+// returns immediately
+pub struct FakeClock {
+    start: DateValue,
+    elapsed: Duration,
+}
+
+impl FakeClock {
+    pub fn new(start: DateValue) -> Self {
+        Self {
+            start,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Clock for FakeClock {
+    fn monotonic(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn wall(&self) -> DateValue {
+        let elapsed = i64::try_from(self.elapsed.as_millis()).unwrap_or(i64::MAX);
+        DateValue { millis: self.start.millis.saturating_add(elapsed) }
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.elapsed = self.elapsed.saturating_add(duration);
+    }
+}
+
+// a point in time with millisecond precision, always in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DateValue {
+    // since 1970-01-01T00:00:00Z
+    pub millis: i64,
+}
+
+// the calendar fields of a date, month and day count from 1
+struct Fields {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millisecond: i64,
+}
+
+impl DateValue {
+    fn from_fields(fields: &Fields) -> Option<Self> {
+        let valid = (-MAX_YEAR..=MAX_YEAR).contains(&fields.year)
+            && (1..=12).contains(&fields.month)
+            && (1..=days_in_month(fields.year, fields.month)).contains(&fields.day)
+            && (0..24).contains(&fields.hour)
+            && (0..60).contains(&fields.minute)
+            && (0..60).contains(&fields.second)
+            && (0..1000).contains(&fields.millisecond);
+        if !valid {
+            return None;
+        }
+        let days = days_from_civil(fields.year, fields.month, fields.day);
+        let seconds = fields.hour * 3600 + fields.minute * 60 + fields.second;
+        Some(Self { millis: days * MILLIS_PER_DAY + seconds * 1000 + fields.millisecond })
+    }
+
+    fn fields(&self) -> Fields {
+        let days = self.millis.div_euclid(MILLIS_PER_DAY);
+        let rest = self.millis.rem_euclid(MILLIS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        Fields {
+            year,
+            month,
+            day,
+            hour: rest / 3_600_000,
+            minute: rest / 60_000 % 60,
+            second: rest / 1000 % 60,
+            millisecond: rest % 1000,
+        }
+    }
+
+    // None when the result does not fit
+    fn add_millis(&self, millis: i64) -> Option<Self> {
+        Some(Self { millis: self.millis.checked_add(millis)? })
+    }
+
+    // 1 for monday through 7 for sunday, as in ISO-8601
+    fn weekday(&self) -> i64 {
+        // the epoch was a thursday
+        (self.millis.div_euclid(MILLIS_PER_DAY) + 3).rem_euclid(7) + 1
+    }
+
+    // accepts `YYYY-MM-DD`, optionally followed by `THH:MM`, seconds,
+    // milliseconds and a `Z` or `+HH:MM` offset, times without an offset are
+    // taken as UTC
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut cursor = Cursor { rest: s };
+        let mut fields = Fields { year: 0, month: 0, day: 0, hour: 0, minute: 0, second: 0, millisecond: 0 };
+        fields.year = cursor.number(4)?;
+        cursor.expect('-')?;
+        fields.month = cursor.number(2)?;
+        cursor.expect('-')?;
+        fields.day = cursor.number(2)?;
+
+        let mut offset = 0;
+        if cursor.expect('T').or_else(|| cursor.expect(' ')).is_some() {
+            fields.hour = cursor.number(2)?;
+            cursor.expect(':')?;
+            fields.minute = cursor.number(2)?;
+            if cursor.expect(':').is_some() {
+                fields.second = cursor.number(2)?;
+                if cursor.expect('.').is_some() {
+                    fields.millisecond = cursor.number(3)?;
+                }
+            }
+            if cursor.expect('Z').is_none() {
+                let sign = match cursor.rest.chars().next() {
+                    Some('+') => 1,
+                    Some('-') => -1,
+                    _ => 0,
+                };
+                if sign != 0 {
+                    cursor.rest = &cursor.rest[1..];
+                    let hours = cursor.number(2)?;
+                    cursor.expect(':');
+                    let minutes = cursor.number(2)?;
+                    offset = sign * (hours * 60 + minutes) * 60_000;
+                }
+            }
+        }
+        if !cursor.rest.is_empty() {
+            return None;
+        }
+        Self::from_fields(&fields)?.add_millis(-offset)
+    }
+}
+
+impl std::fmt::Display for DateValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let d = self.fields();
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", d.year, d.month, d.day, d.hour, d.minute, d.second)?;
+        if d.millisecond != 0 {
+            write!(f, ".{:03}", d.millisecond)?;
+        }
+        write!(f, "Z")
+    }
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl Cursor<'_> {
+    fn number(&mut self, digits: usize) -> Option<i64> {
+        let part = self.rest.get(..digits)?;
+        if !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        self.rest = &self.rest[digits..];
+        part.parse().ok()
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.rest = self.rest.strip_prefix(c)?;
+        Some(())
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// days since the epoch for a proleptic gregorian date, see
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+pub fn module() -> Module {
+    let functions = [
+        NativeFunction::new("now", 0, 0, now),
+        NativeFunction::new("wall", 0, 0, wall),
+        NativeFunction::new("sleep", 1, 1, sleep),
+        NativeFunction::new("date", 3, 6, date),
+        NativeFunction::new("parse", 1, 1, parse),
+    ];
+
+    let mut module = Module::new("time");
+    for function in functions {
+        module.add(function.name, Literal::Native(function));
+    }
+    module
+}
+
+// the field or method called `name` of a date, durations are in seconds
+pub fn member(date: &DateValue, name: &str) -> Option<Literal> {
+    let d = date.fields();
+    let field = match name {
+        "year" => d.year,
+        "month" => d.month,
+        "day" => d.day,
+        "hour" => d.hour,
+        "minute" => d.minute,
+        "second" => d.second,
+        "millisecond" => d.millisecond,
+        "weekday" => date.weekday(),
+        _ => {
+            let native = match name {
+                "add" => NativeFunction::new("add", 1, 1, add),
+                "since" => NativeFunction::new("since", 1, 1, since),
+                "iso" => NativeFunction::new("iso", 0, 0, iso),
+                _ => return None,
+            };
+            return Some(Literal::Native(native.bind(Literal::Date(*date))));
+        }
+    };
+    Some(Literal::Number(field as f32))
+}
+
+fn expect_date(arguments: &[Literal], index: usize, name: &str, line: usize) -> Result<DateValue> {
+    match &arguments[index] {
+        Literal::Date(date) => Ok(*date),
+        other => Err(invalid_argument(line, name, format!("expected a date, got {other}"))),
+    }
+}
+
+// milliseconds on the monotonic clock, for measuring how long things take
+fn now(interpreter: &mut Interpreter, _: Vec<Literal>, _: usize) -> Result<Literal> {
+    Ok(Literal::Number(interpreter.clock.monotonic().as_secs_f64() as f32 * 1000.0))
+}
+
+fn wall(interpreter: &mut Interpreter, _: Vec<Literal>, _: usize) -> Result<Literal> {
+    Ok(Literal::Date(interpreter.clock.wall()))
+}
+
+fn sleep(interpreter: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let millis = expect_number(&args, 0, "time.sleep", line)?;
+    // negative, NaN, infinite and absurdly long durations all fail here
+    let Ok(duration) = Duration::try_from_secs_f32(millis / 1000.0) else {
+        return Err(invalid_argument(line, "time.sleep", format!("cannot sleep for {millis} milliseconds")));
+    };
+    interpreter.clock.sleep(duration);
+    Ok(Literal::Nil)
+}
+
+// time.date(year, month, day) with an optional hour, minute and second
+fn date(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let mut parts = [0; 6];
+    for (i, part) in parts.iter_mut().enumerate().take(args.len()) {
+        *part = expect_integer(&args, i, "time.date", line)?;
+    }
+    let [year, month, day, hour, minute, second] = parts;
+    let fields = Fields { year, month, day, hour, minute, second, millisecond: 0 };
+    match DateValue::from_fields(&fields) {
+        Some(date) => Ok(Literal::Date(date)),
+        None => Err(invalid_argument(line, "time.date", "fields are out of range".to_string())),
+    }
+}
+
+fn parse(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let s = expect_string(&args, 0, "time.parse", line)?;
+    match DateValue::parse(s) {
+        Some(date) => Ok(Literal::Date(date)),
+        None => Err(invalid_argument(line, "time.parse", format!("'{s}' is not an ISO-8601 date"))),
+    }
+}
+
+fn add(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let date = expect_date(&args, 0, "add", line)?;
+    let seconds = expect_number(&args, 1, "add", line)?;
+    let millis = (seconds as f64 * 1000.0).round();
+    // the cast saturates, so anything at the edge of i64 is out of range too
+    let added = if millis.is_finite() && millis.abs() < i64::MAX as f64 {
+        date.add_millis(millis as i64)
+    } else {
+        None
+    };
+    match added {
+        Some(date) => Ok(Literal::Date(date)),
+        None => Err(invalid_argument(line, "add", format!("adding {seconds} seconds leaves the range of dates"))),
+    }
+}
+
+// seconds from `other` to this date, negative if `other` is later
+fn since(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    let date = expect_date(&args, 0, "since", line)?;
+    let other = expect_date(&args, 1, "since", line)?;
+    Ok(Literal::Number(((date.millis as f64 - other.millis as f64) / 1000.0) as f32))
+}
+
+fn iso(_: &mut Interpreter, args: Vec<Literal>, line: usize) -> Result<Literal> {
+    Ok(Literal::String(expect_date(&args, 0, "iso", line)?.to_string()))
+}
@@ -23,6 +23,6 @@ mod test {
 
         // Optionally, we can assert some string if we want:
         // assert_eq!("(Bin Number (Un Minus 4) (Grp 45))", pretty_result);
-        assert_eq!(pretty_result, "(* (- (123)) (group (123)))")
+        assert_eq!(pretty_result, "(* (- (123)) (group (45.67)))")
     }
 }
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod test {
+    use crate::checker::TypeChecker;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+
+    fn check(source: &str) -> Vec<String> {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        let mut parser = Parser::new(scanner.tokens.clone());
+        let statements = parser.parse();
+        assert!(parser.get_errors().is_empty());
+
+        let mut checker = TypeChecker::new();
+        checker.check(&statements);
+        checker.get_errors().iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_mismatches_with_lines() {
+        let errors = check("var x: Num = 1;\nvar flag = true;\nprint x + flag;\nx = \"s\";");
+        assert_eq!(errors, [
+            "[3]: Cannot apply + to Num and Bool",
+            "[4]: 'x' is declared as Num but assigned Str",
+        ]);
+    }
+
+    #[test]
+    fn unannotated_code_stays_dynamic() {
+        let errors = check("var d = 1; d = \"now a string\"; print d + \"!\"; var (a, b) = (d, 2);");
+        assert!(errors.is_empty());
+    }
+}
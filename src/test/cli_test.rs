@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod test {
+    use crate::{Args, Command};
+    use clap::Parser;
+
+    fn parse(words: &[&str]) -> Args {
+        Args::try_parse_from(std::iter::once("brainrot").chain(words.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn options_go_before_or_after_a_command() {
+        for words in [["--dialect", "slang", "check", "f.br"], ["check", "--dialect", "slang", "f.br"]] {
+            let args = parse(&words);
+            assert_eq!(args.dialect, "slang");
+            assert!(matches!(args.command, Some(Command::Check { file_name }) if file_name == "f.br"));
+        }
+    }
+
+    #[test]
+    fn interpreter_options_apply_to_commands() {
+        for words in [["test", "--seed", "1", "."], ["--seed", "1", "test", "."]] {
+            let args = parse(&words);
+            assert_eq!(args.seed, Some(1));
+            assert!(matches!(args.command, Some(Command::Test { .. })));
+        }
+    }
+
+    #[test]
+    fn words_after_the_script_belong_to_it() {
+        let args = parse(&["--dialect", "slang", "f.br", "check", "--seed", "1"]);
+        assert_eq!(args.dialect, "slang");
+        assert_eq!(args.seed, None);
+        let Some(Command::Run(words)) = args.command else {
+            panic!("expected a script to run");
+        };
+        assert_eq!(words, ["f.br", "check", "--seed", "1"]);
+    }
+}
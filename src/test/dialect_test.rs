@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod test {
+    use crate::dialect::{self, Dialect};
+    use crate::scanner::Scanner;
+    use crate::token::TokenType;
+    use crate::translate::translate;
+    use std::rc::Rc;
+
+    fn token_types(source: &str, dialect: Dialect) -> Vec<TokenType> {
+        let mut scanner = Scanner::with_dialect(source.to_string(), Rc::new(dialect));
+        scanner.scan_tokens();
+        assert!(scanner.get_errors().is_empty());
+        scanner.tokens.iter().map(|token| *token.get_type()).collect()
+    }
+
+    #[test]
+    fn respelled_keywords_scan_to_the_same_tokens() {
+        let slang = Dialect::load("slang").unwrap();
+        let types = token_types("bet x = based; yap var;", slang);
+        let expected = vec![
+            TokenType::Var,
+            TokenType::Identifier,
+            TokenType::Equal,
+            TokenType::True,
+            TokenType::SemiColon,
+            TokenType::Print,
+            TokenType::Identifier,
+            TokenType::SemiColon,
+            TokenType::EOF,
+        ];
+        assert_eq!(types, expected);
+
+        // swapping two spellings is allowed
+        let swapped = Dialect::parse("# swap\nvar = print\nprint = var").unwrap();
+        assert_eq!(token_types("print", swapped)[0], TokenType::Var);
+    }
+
+    #[test]
+    fn dialect_files_and_pragmas() {
+        let error = Dialect::parse("say = shout").unwrap_err();
+        assert_eq!(error.to_string(), "[1]: 'shout' is not a keyword");
+        let error = Dialect::parse("say = print\nsay = var").unwrap_err();
+        assert_eq!(error.to_string(), "[2]: 'say' is used for more than one keyword");
+        let error = Dialect::parse("if = print").unwrap_err();
+        assert_eq!(error.to_string(), "'if' is still the spelling of the 'if' keyword, respell it as well");
+
+        assert_eq!(dialect::pragma("// notes\n\n// dialect: slang\nbet x = 1;"), Some("slang"));
+        assert_eq!(dialect::pragma("var x = 1;\n// dialect: slang"), None);
+    }
+
+    #[test]
+    fn pragma_paths_are_relative_to_the_source() {
+        let dir = std::env::temp_dir().join(format!("brainrot_dialect_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("team.dialect"), "say = print").unwrap();
+
+        let team = Dialect::load_from("team.dialect", &dir).unwrap();
+        assert_eq!(token_types("say 1;", team)[0], TokenType::Print);
+        assert!(Dialect::load("team.dialect").is_err());
+        // presets are found wherever the source is
+        assert!(Dialect::load_from("slang", &dir).is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn translation_keeps_trivia() {
+        let source = "// dialect: slang\nbet  x = based; /* yap */\n\tyap \"bet\"; // cap\n";
+        let slang = Rc::new(Dialect::load("slang").unwrap());
+        let standard = Dialect::default();
+        let translated = translate(source, slang.clone(), &standard, "standard").unwrap();
+        let expected = "// dialect: standard\nvar  x = true; /* yap */\n\tprint \"bet\"; // cap\n";
+        assert_eq!(translated, expected);
+
+        let back = translate(&translated, Rc::new(standard), &slang, "slang").unwrap();
+        assert_eq!(back, source);
+
+        let errors = translate("bet print = 1;", slang, &Dialect::default(), "standard").unwrap_err();
+        assert_eq!(errors[0].to_string(), "[1]: 'print' is an identifier here but a keyword in the target dialect");
+    }
+}
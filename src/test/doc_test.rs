@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod test {
+    use crate::doc::{self, DocFormat, ModuleDoc};
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+    use crate::statements::Statement;
+
+    fn parse(source: &str) -> (Parser, Vec<Statement>) {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        assert!(scanner.get_errors().is_empty());
+        let mut parser = Parser::new(scanner.tokens.clone());
+        let statements = parser.parse();
+        assert!(parser.get_errors().is_empty());
+        (parser, statements)
+    }
+
+    #[test]
+    fn doc_comments_attach_to_the_next_declaration() {
+        let source = "//! Geometry helpers.
+            /// Full turn.
+            /// In radians.
+            const tau = 6.283;
+            //// an ordinary comment
+            var x = 1;
+            /// Ignored, documents no declaration.
+            print x;
+            enum Shape {
+                /// Radius r
+                Circle(r),
+                Point
+            }";
+        let (parser, statements) = parse(source);
+        assert_eq!(parser.get_module_doc(), Some("Geometry helpers."));
+        let Statement::Const(_, _, _, Some(doc)) = &statements[0] else { panic!() };
+        assert_eq!(doc, "Full turn.\nIn radians.");
+        assert!(matches!(&statements[1], Statement::Var(_, _, _, None)));
+        let Statement::Enum(_, variants, None) = &statements[3] else { panic!() };
+        assert_eq!(variants[0].doc.as_deref(), Some("Radius r"));
+        assert_eq!(variants[1].doc, None);
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let (parser, statements) = parse("/// Full turn.\nconst tau: Num = 6.283;\nenum E { /// first\n A(x), B }");
+        let module = ModuleDoc::new("geometry", parser.get_module_doc(), &statements);
+        let expected = "# geometry
+
+## Constants
+
+### `tau: Num`
+
+Full turn.
+
+## Enums
+
+### `enum E`
+
+- `A(x)`: first
+- `B`
+
+";
+        assert_eq!(doc::render(&[module], DocFormat::Markdown), expected);
+    }
+
+    #[test]
+    fn renders_html() {
+        let source = "//! Shapes & sizes.\n//!\n//! Uses <b> \"tags\".\n/// Full turn.\nconst tau = 6.283;\nenum E { /// a < b\n A(x), B }";
+        let (parser, statements) = parse(source);
+        let module = ModuleDoc::new("geo<metry>", parser.get_module_doc(), &statements);
+        let expected = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>API documentation</title>
+</head>
+<body>
+<section>
+<h1>geo&lt;metry&gt;</h1>
+<p>Shapes &amp; sizes.</p>
+<p>Uses &lt;b&gt; &quot;tags&quot;.</p>
+<h2>Constants</h2>
+<h3><code>tau</code></h3>
+<p>Full turn.</p>
+<h2>Enums</h2>
+<h3><code>enum E</code></h3>
+<ul>
+<li><code>A(x)</code>: a &lt; b</li>
+<li><code>B</code></li>
+</ul>
+</section>
+</body>
+</html>
+";
+        assert_eq!(doc::render(&[module], DocFormat::Html), expected);
+    }
+}
@@ -0,0 +1,456 @@
+#[cfg(test)]
+mod test {
+    use crate::interpreter::{InterpretError, Interpreter};
+    use crate::parser::Parser;
+    use crate::resolver::Resolver;
+    use crate::scanner::Scanner;
+    use crate::stdlib::time::{DateValue, FakeClock};
+    use crate::token::{Literal, Token, TokenType};
+    use anyhow::Result;
+    use std::collections::HashSet;
+
+    fn run(source: &str) -> (Interpreter, Result<()>) {
+        run_with(Interpreter::new(), source)
+    }
+
+    fn run_with(mut interpreter: Interpreter, source: &str) -> (Interpreter, Result<()>) {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        assert!(scanner.get_errors().is_empty());
+        let mut parser = Parser::new(scanner.tokens.clone());
+        let statements = parser.parse();
+        assert!(parser.get_errors().is_empty());
+
+        let result = interpreter.interpret(statements);
+        (interpreter, result)
+    }
+
+    fn get(interpreter: &Interpreter, name: &str) -> Literal {
+        let token = Token::new(TokenType::Identifier, name.to_string(), None, 1);
+        interpreter.environment.get(&token).unwrap()
+    }
+
+    #[test]
+    fn tuple_destructuring() {
+        let (interpreter, result) = run("var (a, (b, c)) = (1, (\"two\", 3)); (a, c) = (c, a);");
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "a"), Literal::Number(3.0));
+        assert_eq!(get(&interpreter, "b"), Literal::String("two".to_string()));
+        assert_eq!(get(&interpreter, "c"), Literal::Number(1.0));
+    }
+
+    #[test]
+    fn tuple_arity_mismatch() {
+        let (_, result) = run("var (x, y) = (1, 2, 3);");
+        let error = result.unwrap_err().to_string();
+        assert_eq!(error, "[1]: Pattern expects 2 values but the tuple has 3");
+    }
+
+    #[test]
+    fn comparisons_evaluate_left_to_right() {
+        let (interpreter, result) = run("var a = 0; var b = (a = 1) > (a = 2); var last = a;");
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "b"), Literal::Boolean(false));
+        assert_eq!(get(&interpreter, "last"), Literal::Number(2.0));
+    }
+
+    #[test]
+    fn enum_variants() {
+        let source = "enum Shape { Circle(r), Rect(w, h), Empty }
+            var c = Shape.Circle(2);
+            var same = c == Shape.Circle(2);
+            var r = c.r;
+            var rect = Shape.Rect(1, 2);";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "same"), Literal::Boolean(true));
+        assert_eq!(get(&interpreter, "r"), Literal::Number(2.0));
+        assert_eq!(get(&interpreter, "rect").to_string(), "Shape.Rect(w: 1, h: 2)");
+    }
+
+    #[test]
+    fn constants_reject_assignment() {
+        let (_, result) = run("const MAX = 3; MAX = 4;");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: Cannot assign to constant 'MAX'");
+
+        let mut scanner = Scanner::new("const (a, b) = (1, 2);\n(b, c) = (3, 4);\nvar a;".to_string());
+        scanner.scan_tokens();
+        let statements = Parser::new(scanner.tokens.clone()).parse();
+        let mut resolver = Resolver::new(HashSet::new());
+        resolver.resolve(&statements);
+        let errors: Vec<String> = resolver.get_errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["[2]: Cannot assign to constant 'b'", "[3]: Cannot redefine constant 'a'"]);
+    }
+
+    #[test]
+    fn builtin_modules_can_be_redeclared() {
+        let source = "var time = 1; time = time + 1; const math = 3;";
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        let statements = Parser::new(scanner.tokens.clone()).parse();
+        let mut interpreter = Interpreter::new();
+        let mut resolver = Resolver::new(interpreter.environment.get_constants().clone());
+        resolver.resolve(&statements);
+        assert!(resolver.get_errors().is_empty());
+        assert!(interpreter.interpret(statements).is_ok());
+        assert_eq!(get(&interpreter, "time"), Literal::Number(2.0));
+        assert_eq!(get(&interpreter, "math"), Literal::Number(3.0));
+    }
+
+    #[test]
+    fn spread_into_tuples_and_calls() {
+        let source = "enum Shape { Rect(w, h) }
+            var a = (1, 2);
+            var t = (0, ...a, ...(3,));
+            var r = Shape.Rect(...a);";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "t").to_string(), "(0, 1, 2, 3)");
+        assert_eq!(get(&interpreter, "r").to_string(), "Shape.Rect(w: 1, h: 2)");
+
+        let (_, result) = run("var n = 1;\nvar t = (...n);");
+        assert_eq!(result.unwrap_err().to_string(), "[2]: Cannot spread 1, expected a tuple, range or map");
+    }
+
+    #[test]
+    fn nil_safe_navigation() {
+        let source = "enum Opt { Some(v) }
+            var cfg = nil;
+            var port = cfg?.server.port;
+            var called = cfg?.make(1);
+            var first = cfg?[0];
+            var fallback = cfg?.port ?? 8080;
+            var inner = Opt.Some((1, 2))?.v[1];";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "port"), Literal::Nil);
+        assert_eq!(get(&interpreter, "called"), Literal::Nil);
+        assert_eq!(get(&interpreter, "first"), Literal::Nil);
+        assert_eq!(get(&interpreter, "fallback"), Literal::Number(8080.0));
+        assert_eq!(get(&interpreter, "inner"), Literal::Number(2.0));
+    }
+
+    #[test]
+    fn ranges_are_lazy_values() {
+        let source = "var big = 0..1000000000;
+            var last = big[999];
+            var stepped = (...1..=10 step 3);
+            var t = (10, 20, 30, 40, 50);
+            var slice = t[1..3];
+            var reversed = t[4..=0 step -2];";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "big").to_string(), "0..1000000000");
+        assert_eq!(get(&interpreter, "last"), Literal::Number(999.0));
+        assert_eq!(get(&interpreter, "stepped").to_string(), "(1, 4, 7, 10)");
+        assert_eq!(get(&interpreter, "slice").to_string(), "(20, 30)");
+        assert_eq!(get(&interpreter, "reversed").to_string(), "(50, 30, 10)");
+
+        let source = "var thirds = (...0..=0.9 step 0.3);
+            var open = (...0..0.9 step 0.3);
+            var has_end = 0.9 in 0..=0.9 step 0.3;
+            var off_step = 0.7 in 0..=0.9 step 0.3;";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "thirds").to_string(), "(0, 0.3, 0.6, 0.9)");
+        assert_eq!(get(&interpreter, "open").to_string(), "(0, 0.3, 0.6)");
+        assert_eq!(get(&interpreter, "has_end"), Literal::Boolean(true));
+        assert_eq!(get(&interpreter, "off_step"), Literal::Boolean(false));
+
+        let (_, result) = run("var r = 0..math.inf;");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: Range bounds and step must be finite numbers, got inf");
+        let (_, result) = run("var t = (...0..10 step 0.0000001);");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "[1]: Range 0..10 step 0.0000001 is too large to expand, it has more than 1048576 elements"
+        );
+        let (_, result) = run("iter.map(0..math.pow(10, 30), math.abs);");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "[1]: Range 0..1000000000000000000000000000000 is too large to expand, it has more than 1048576 elements"
+        );
+    }
+
+    #[test]
+    fn membership() {
+        let source = "var in_tuple = 2 in (1, 2, 3);
+            var not_in_tuple = 4 not in (1, 2);
+            var substring = \"ell\" in \"hello\";
+            var on_step = 5 in 0..10 step 5;
+            var past_end = 5 in 0..5;";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "in_tuple"), Literal::Boolean(true));
+        assert_eq!(get(&interpreter, "not_in_tuple"), Literal::Boolean(true));
+        assert_eq!(get(&interpreter, "substring"), Literal::Boolean(true));
+        assert_eq!(get(&interpreter, "on_step"), Literal::Boolean(true));
+        assert_eq!(get(&interpreter, "past_end"), Literal::Boolean(false));
+    }
+
+    #[test]
+    fn string_methods_count_characters() {
+        let source = "var s = \"héllo\";
+            var length = s.len();
+            var second = s[1];
+            var found = s.find(\"llo\");
+            var shout = s.upper().pad_left(7, \"*\");
+            var parts = \"a,b\".split(\",\");";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "length"), Literal::Number(5.0));
+        assert_eq!(get(&interpreter, "second"), Literal::String("é".to_string()));
+        assert_eq!(get(&interpreter, "found"), Literal::Number(2.0));
+        assert_eq!(get(&interpreter, "shout"), Literal::String("**HÉLLO".to_string()));
+        assert_eq!(get(&interpreter, "parts").to_string(), "(a, b)");
+
+        let (_, result) = run("var n = \"ab\".repeat(1, 2);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: repeat expects 1 arguments but got 2");
+        let (_, result) = run("var n = \"ab\".repeat(100000000000000000000);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: repeat: the result would be longer than 16777216 bytes");
+        let (_, result) = run("var n = \"ab\".pad_left(100000000000000000000);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: pad_left: the result would be longer than 16777216 bytes");
+    }
+
+    #[test]
+    fn math_module() {
+        let source = "var root = math.sqrt(16);
+            var smallest = math.min(3, 1, 2);
+            var clamped = math.clamp(15, 0, 10);
+            var nan = math.is_nan(math.sqrt_or_nan(-1));";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "root"), Literal::Number(4.0));
+        assert_eq!(get(&interpreter, "smallest"), Literal::Number(1.0));
+        assert_eq!(get(&interpreter, "clamped"), Literal::Number(10.0));
+        assert_eq!(get(&interpreter, "nan"), Literal::Boolean(true));
+
+        let (_, result) = run("var bad = math.log(-2);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: log is undefined for -2");
+        let (_, result) = run("var bad = math.clamp(1, math.nan, 2);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: clamp: bounds cannot be NaN, got NaN and 2");
+    }
+
+    #[test]
+    fn fs_module_round_trip() {
+        let dir = std::env::temp_dir().join(format!("brainrot_fs_test_{}", std::process::id()));
+        let dir = dir.to_string_lossy().replace('\\', "/");
+        let source = format!(
+            "fs.mkdir(\"{dir}\");
+            fs.write_text(\"{dir}/a.txt\", \"one\");
+            fs.append(\"{dir}/a.txt\", \"\ntwo\");
+            var lines = fs.lines(\"{dir}/a.txt\");
+            var names = fs.list_dir(\"{dir}\");
+            fs.remove(\"{dir}/a.txt\");
+            fs.remove(\"{dir}\");
+            var gone = fs.exists(\"{dir}\");"
+        );
+        let (interpreter, result) = run(&source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "lines").to_string(), "(one, two)");
+        assert_eq!(get(&interpreter, "names").to_string(), "(a.txt,)");
+        assert_eq!(get(&interpreter, "gone"), Literal::Boolean(false));
+
+        let (_, result) = run(&format!("fs.read_text(\"{dir}/missing\");"));
+        assert!(result.unwrap_err().to_string().starts_with("[1]: fs.read_text failed for"));
+    }
+
+    #[test]
+    fn random_module_is_reproducible() {
+        let source = "random.seed(7);
+            var roll = random.int(1, 6);
+            var pick = random.choice((\"a\", \"b\", \"c\"));
+            var order = random.shuffle((1, 2, 3, 4, 5));
+            var few = random.sample((1, 2, 3, 4, 5), 3);";
+        let (first, result) = run(source);
+        assert!(result.is_ok());
+        let (second, _) = run(source);
+        for name in ["roll", "pick", "order", "few"] {
+            assert_eq!(get(&first, name), get(&second, name));
+        }
+        let Literal::Number(roll) = get(&first, "roll") else { panic!() };
+        assert!((1.0..=6.0).contains(&roll) && roll.fract() == 0.0);
+
+        let (_, result) = run("random.sample((1, 2), 3);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: random.sample: cannot take 3 values from a tuple of 2");
+        let (_, result) = run("random.int(-math.pow(10, 19), math.pow(10, 19));");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: random.int: -10000000000000000000 is out of range");
+        let (_, result) = run("random.int(-math.pow(2, 63), math.pow(2, 62));");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn time_module_with_fake_clock() {
+        let mut interpreter = Interpreter::new();
+        let start = DateValue::parse("2024-02-28T23:30:00+01:00").unwrap();
+        interpreter.clock = Box::new(FakeClock::new(start));
+        let source = "var before = time.now();
+            time.sleep(90000);
+            var waited = time.now() - before;
+            var now = time.wall().iso();
+            var leap = time.parse(\"2024-02-28\").add(86400);
+            var fields = (leap.month, leap.day, leap.weekday);
+            var later = time.date(2024, 3, 1) > leap;";
+        let (interpreter, result) = run_with(interpreter, source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "waited"), Literal::Number(90000.0));
+        assert_eq!(get(&interpreter, "now"), Literal::String("2024-02-28T22:31:30Z".to_string()));
+        assert_eq!(get(&interpreter, "fields").to_string(), "(2, 29, 4)");
+        assert_eq!(get(&interpreter, "later"), Literal::Boolean(true));
+
+        let (_, result) = run("time.parse(\"2023-02-29\");");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: time.parse: '2023-02-29' is not an ISO-8601 date");
+        let (_, result) = run("time.sleep(math.inf);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: time.sleep: cannot sleep for inf milliseconds");
+        let (_, result) = run("time.parse(\"2024-01-01\").add(math.inf);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: add: adding inf seconds leaves the range of dates");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut interpreter = Interpreter::new();
+        let text = "{\"name\": \"brainrot\", \"tags\": [\"a\", null], \"nested\": {\"n\": -1.5e2}}";
+        interpreter.environment.define_builtin("text", Literal::String(text.to_string()));
+        let source = "var doc = json.parse(text);
+            var n = doc.nested[\"n\"];
+            var tags = doc.tags;
+            var has = \"name\" in doc;
+            var out = json.stringify(doc);
+            var same = json.parse(out) == doc;";
+        let (interpreter, result) = run_with(interpreter, source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "n"), Literal::Number(-150.0));
+        assert_eq!(get(&interpreter, "tags").to_string(), "(a, nil)");
+        assert_eq!(get(&interpreter, "has"), Literal::Boolean(true));
+        let out = "{\"name\":\"brainrot\",\"tags\":[\"a\",null],\"nested\":{\"n\":-150}}";
+        assert_eq!(get(&interpreter, "out"), Literal::String(out.to_string()));
+        assert_eq!(get(&interpreter, "same"), Literal::Boolean(true));
+
+        let mut interpreter = Interpreter::new();
+        interpreter.environment.define_builtin("text", Literal::String("[1,\n 2,]".to_string()));
+        let (_, result) = run_with(interpreter, "json.parse(text);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: json.parse: unexpected character ']' at line 2, column 4");
+
+        let (_, result) = run("json.stringify((1, math.sqrt));");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: json.stringify: cannot convert <native fn sqrt> to JSON");
+        let (interpreter, result) = run("var wide = json.stringify((1,), 100000000000000000000);");
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "wide"), Literal::String(format!("[\n{}1\n]", " ".repeat(10))));
+    }
+
+    #[test]
+    fn regex_module() {
+        let source = "const day = regex.compile(\"(?P<month>\\d{2})/(?P<day>\\d{2})\");
+            var found = day.match(\"é on 05/17\");
+            var all = day.find_all(\"01/02 and 03/04\");
+            var swapped = day.replace(\"05/17\", \"${day}.$1\");
+            var parts = regex.split(\",\\s*\", \"a, b,c\");";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(
+            get(&interpreter, "found").to_string(),
+            "{text: 05/17, start: 5, end: 10, groups: (05, 17), named: {month: 05, day: 17}}"
+        );
+        assert_eq!(get(&interpreter, "all").to_string(), "(01/02, 03/04)");
+        assert_eq!(get(&interpreter, "swapped"), Literal::String("17.05".to_string()));
+        assert_eq!(get(&interpreter, "parts").to_string(), "(a, b, c)");
+
+        let (_, result) = run("regex.compile(\"(\");");
+        assert!(result.unwrap_err().to_string().starts_with("[1]: regex.compile: invalid pattern"));
+        let (_, result) = run("regex.split(\"(\", \"a\");");
+        assert!(result.unwrap_err().to_string().starts_with("[1]: regex.split: invalid pattern"));
+    }
+
+    #[test]
+    fn iter_module_takes_callables() {
+        let source = "var xs = (3, -1, 2, -1);
+            var absolute = iter.map(xs, math.abs);
+            var largest = iter.reduce(xs, math.max);
+            var sorted = iter.sort((1.5, 1.2, 0.5, 1.1), math.floor);
+            var pairs = iter.zip(xs, \"ab\".chars());
+            var groups = iter.group_by((1.5, 2, 2.5), math.floor);
+            var distinct = iter.unique(xs);
+            var prefixes = iter.filter((\"a\", \"c\", \"ab\"), \"abc\".starts_with);";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "absolute").to_string(), "(3, 1, 2, 1)");
+        assert_eq!(get(&interpreter, "largest"), Literal::Number(3.0));
+        assert_eq!(get(&interpreter, "sorted").to_string(), "(0.5, 1.5, 1.2, 1.1)");
+        assert_eq!(get(&interpreter, "pairs").to_string(), "((3, a), (-1, b))");
+        assert_eq!(get(&interpreter, "groups").to_string(), "{1: (1.5,), 2: (2, 2.5)}");
+        assert_eq!(get(&interpreter, "distinct").to_string(), "(3, -1, 2)");
+        assert_eq!(get(&interpreter, "prefixes").to_string(), "(a, ab)");
+
+        let (_, result) = run("iter.filter((1, 2), math.abs);");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: iter.filter: predicate returned 1, expected a boolean");
+
+        let (_, result) = run("iter.sort((...0..35, math.nan));");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: iter.sort: cannot order NaN");
+        let (_, result) = run("iter.sort(((0, \"a\"), (1, 1), (1, \"b\")));");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: iter.sort: cannot order (1, 1) and (1, b)");
+    }
+
+    #[test]
+    fn sys_and_env_modules() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_args(&["--flag".to_string(), "two".to_string()]);
+        let source = "var args = sys.args;
+            var missing = env.get(\"BRAINROT_TEST_UNSET\");
+            sys.exit(3);
+            var after = 1;";
+        let (interpreter, result) = run_with(interpreter, source);
+        assert_eq!(get(&interpreter, "args").to_string(), "(--flag, two)");
+        assert_eq!(get(&interpreter, "missing"), Literal::Nil);
+        let error = result.unwrap_err();
+        assert!(matches!(error.downcast_ref(), Some(InterpretError::Exit(3, 3))));
+        let token = Token::new(TokenType::Identifier, "after".to_string(), None, 4);
+        assert!(interpreter.environment.get(&token).is_err());
+    }
+
+    // env.set changes the environment of the whole process, which is not safe
+    // while other test threads read it, so the test runs itself again as a
+    // child process that runs nothing else
+    #[test]
+    fn env_set_in_a_child_process() {
+        if std::env::var("BRAINROT_ENV_CHILD").is_err() {
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "test::interpreter_test::test::env_set_in_a_child_process", "--test-threads=1"])
+                .env("BRAINROT_ENV_CHILD", "1")
+                .output()
+                .unwrap();
+            let report = String::from_utf8_lossy(&output.stdout);
+            assert!(output.status.success() && report.contains("1 passed"), "{report}");
+            return;
+        }
+
+        let source = "env.set(\"BRAINROT_TEST_VAR\", \"set\");
+            var value = env.get(\"BRAINROT_TEST_VAR\");
+            var inherited = process.run(\"sh\", (\"-c\", \"echo $BRAINROT_TEST_VAR\")).stdout;";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "value"), Literal::String("set".to_string()));
+        assert_eq!(get(&interpreter, "inherited"), Literal::String("set\n".to_string()));
+    }
+
+    #[test]
+    fn process_run_collects_output() {
+        let source = "var result = process.run(\"sh\", (\"-c\", \"echo hi; echo err >&2; exit 3\"));";
+        let (interpreter, result) = run(source);
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "result").to_string(), "{stdout: hi\n, stderr: err\n, status: 3}");
+
+        let (_, result) = run("process.run(\"brainrot-no-such-program\");");
+        let error = result.unwrap_err().to_string();
+        assert!(error.starts_with("[1]: process.run failed for 'brainrot-no-such-program': "), "{error}");
+    }
+
+    #[test]
+    fn assert_statement() {
+        let (_, result) = run("assert 1 < 2; assert 2 < 1, \"two is \" + 2;");
+        assert_eq!(result.unwrap_err().to_string(), "[1]: Assertion failed: two is 2");
+
+        // test bodies only run under `brainrot test`
+        let (interpreter, result) = run("var x = 1; test \"skipped\" { x = 2; assert false; }");
+        assert!(result.is_ok());
+        assert_eq!(get(&interpreter, "x"), Literal::Number(1.0));
+    }
+}
@@ -1 +1,7 @@
 pub mod ast_test;
+pub mod interpreter_test;
+pub mod checker_test;
+pub mod dialect_test;
+pub mod doc_test;
+pub mod runner_test;
+pub mod cli_test;
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod test {
+    use crate::interpreter::Interpreter;
+    use crate::parser::Parser;
+    use crate::runner::{self, TestFormat, TestResult};
+    use crate::scanner::Scanner;
+    use crate::statements::Statement;
+    use crate::stdlib::time::{DateValue, FakeClock};
+
+    fn parse(source: &str) -> Vec<Statement> {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        assert!(scanner.get_errors().is_empty());
+        let mut parser = Parser::new(scanner.tokens.clone());
+        let statements = parser.parse();
+        assert!(parser.get_errors().is_empty());
+        statements
+    }
+
+    const SOURCE: &str = "var count = 0;
+        test \"first\" {
+            count = count + 1;
+            assert count == 1;
+        }
+        test \"second\" {
+            count = count + 1;
+            assert count == 2, \"count is \" + count;
+        }";
+
+    #[test]
+    fn tests_run_in_isolation() {
+        let results = runner::run_tests("a_test.br", parse(SOURCE), None, Interpreter::new);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].failure, None);
+        assert_eq!(results[1].line, 6);
+        assert_eq!(results[1].failure.as_deref(), Some("[8]: Assertion failed: count is 1"));
+
+        let filtered = runner::run_tests("a_test.br", parse(SOURCE), Some("fir"), Interpreter::new);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name, "first");
+    }
+
+    #[test]
+    fn tests_use_the_given_interpreters() {
+        let source = "test \"frozen\" { assert time.wall().iso() == \"2024-01-01T00:00:00Z\"; }";
+        let frozen = || {
+            let mut interpreter = Interpreter::new();
+            interpreter.clock = Box::new(FakeClock::new(DateValue::parse("2024-01-01").unwrap()));
+            interpreter
+        };
+        let results = runner::run_tests("a_test.br", parse(source), None, frozen);
+        assert_eq!(results[0].failure, None);
+    }
+
+    #[test]
+    fn file_failures_keep_the_errors() {
+        let errors = ["[3, 7]: Unterminated String".to_string(), "[5]: Invalid Syntax".to_string()];
+        let result = TestResult::file_failure("a_test.br", &errors);
+        assert_eq!(result.line, 3);
+        assert_eq!(result.failure.as_deref(), Some("[3, 7]: Unterminated String; [5]: Invalid Syntax"));
+        assert_eq!(TestResult::file_failure("a_test.br", &["Unknown dialect".to_string()]).line, 0);
+    }
+
+    #[test]
+    fn human_report() {
+        let results = runner::run_tests("a_test.br", parse(SOURCE), None, Interpreter::new);
+        let expected = "PASS a_test.br:2 first
+FAIL a_test.br:6 second
+    a_test.br: [8]: Assertion failed: count is 1
+
+1 passed, 1 failed
+";
+        assert_eq!(runner::report(&results, TestFormat::Human), expected);
+    }
+
+    #[test]
+    fn tap_report() {
+        let results = runner::run_tests("a_test.br", parse(SOURCE), None, Interpreter::new);
+        let expected = "TAP version 13
+1..2
+ok 1 - a_test.br > first
+not ok 2 - a_test.br > second
+  ---
+  message: '[8]: Assertion failed: count is 1'
+  file: 'a_test.br'
+  line: 6
+  ...
+";
+        assert_eq!(runner::report(&results, TestFormat::Tap), expected);
+    }
+
+    #[test]
+    fn junit_report_escapes_names_and_messages() {
+        let results = vec![
+            TestResult {
+                file: "a_test.br".to_string(),
+                name: "a < b & \"c\" > d".to_string(),
+                line: 2,
+                failure: Some("[3]: Assertion failed: <1> & \"2\"\nsecond line".to_string()),
+            },
+            TestResult {
+                file: "a_test.br".to_string(),
+                name: "passes".to_string(),
+                line: 5,
+                failure: None,
+            },
+        ];
+        let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites tests=\"2\" failures=\"1\">
+  <testsuite name=\"a_test.br\" tests=\"2\" failures=\"1\">
+    <testcase name=\"a &lt; b &amp; &quot;c&quot; &gt; d\" classname=\"a_test.br\" file=\"a_test.br\" line=\"2\">
+      <failure message=\"[3]: Assertion failed: &lt;1&gt; &amp; &quot;2&quot;&#10;second line\"/>
+    </testcase>
+    <testcase name=\"passes\" classname=\"a_test.br\" file=\"a_test.br\" line=\"5\"/>
+  </testsuite>
+</testsuites>
+";
+        assert_eq!(runner::report(&results, TestFormat::Junit), expected);
+    }
+}
@@ -1,14 +1,61 @@
+use crate::enums::{EnumDef, EnumValue};
+use crate::map::MapValue;
+use crate::range::RangeValue;
+use crate::stdlib::regex::RegexValue;
+use crate::stdlib::time::DateValue;
+use crate::stdlib::{Module, NativeFunction};
+use std::rc::Rc;
 use strum_macros::Display;
 
-#[derive(Display, Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     Number(f32),
     String(String),
     Boolean(bool),
+    Tuple(Vec<Literal>),
+    Enum(Rc<EnumDef>),
+    // a variant with fields that still has to be called to build a value,
+    // holds the index of the variant in the enum
+    Constructor(Rc<EnumDef>, usize),
+    Variant(Box<EnumValue>),
+    Range(RangeValue),
+    Native(NativeFunction),
+    Module(Rc<Module>),
+    Date(DateValue),
+    Map(Rc<MapValue>),
+    Regex(RegexValue),
     Nil,
 }
 
-#[derive(Display, Clone, Copy, Debug, PartialEq)]
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Number(n) => write!(f, "{n}"),
+            Literal::String(s) => write!(f, "{s}"),
+            Literal::Boolean(b) => write!(f, "{b}"),
+            Literal::Tuple(elements) => {
+                let inner: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                if inner.len() == 1 {
+                    write!(f, "({},)", inner[0])
+                } else {
+                    write!(f, "({})", inner.join(", "))
+                }
+            }
+            Literal::Enum(def) => write!(f, "<enum {}>", def.name),
+            Literal::Constructor(def, i) => write!(f, "<variant {}.{}>", def.name, def.variants[*i].name),
+            Literal::Variant(value) => write!(f, "{value}"),
+            Literal::Range(range) => write!(f, "{range}"),
+            Literal::Native(native) => write!(f, "{native}"),
+            Literal::Module(module) => write!(f, "{module}"),
+            Literal::Date(date) => write!(f, "{date}"),
+            Literal::Map(map) => write!(f, "{map}"),
+            Literal::Regex(re) => write!(f, "{re}"),
+            Literal::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+#[derive(Display, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum TokenType {
     // Single Character Tokens
@@ -16,6 +63,9 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
     Comma,
     Dot,
     Minus,
@@ -33,20 +83,41 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    QuestionDot,
+    QuestionLeftBracket,
+    QuestionQuestion,
+    DotDot,
+
+    // three character tokens
+    DotDotEqual,
+    Ellipsis,
+
+    // `not in`, made by the parser out of the two keyword tokens
+    NotIn,
+
+    // a `///` line documenting the next declaration or a `//!` line
+    // documenting the file, the parser takes them out of the token stream
+    DocComment,
 
     // literals
+    Identifier,
     String,
     Number,
 
     // keywords
     And,
+    Assert,
     Class,
+    Const,
     Else,
+    Enum,
     False,
     Fun,
     For,
     If,
+    In,
     Nil,
+    Not,
     Or,
     Print,
     Return,
@@ -83,7 +154,7 @@ impl Token {
     pub fn get_literal(&mut self) -> &Literal {
         self.literal.as_ref().unwrap()
     }
-    pub fn get_line(&mut self) -> usize {
+    pub fn get_line(&self) -> usize {
         self.line
     }
 }
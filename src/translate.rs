@@ -0,0 +1,54 @@
+use crate::dialect::{self, Dialect};
+use crate::scanner::Scanner;
+use crate::token::TokenType;
+use anyhow::Error;
+use std::rc::Rc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TranslateError {
+    #[error("[{0}]: '{1}' is an identifier here but a keyword in the target dialect")]
+    KeywordCollision(usize, String),
+}
+
+// rewrites the keywords of `source` from one dialect to another, everything
+// else, identifiers, literals, comments and whitespace, is copied unchanged,
+// the name in a dialect pragma is replaced by `to_name`
+pub fn translate(source: &str, from: Rc<Dialect>, to: &Dialect, to_name: &str) -> std::result::Result<String, Vec<Error>> {
+    let mut scanner = Scanner::with_dialect(source.to_string(), from.clone());
+    scanner.scan_tokens();
+    if !scanner.get_errors().is_empty() {
+        return Err(scanner.take_errors());
+    }
+
+    let pragma = dialect::pragma_span(source);
+    let mut output = String::with_capacity(source.len());
+    let mut offset = 0;
+    let mut errors = vec![];
+    for piece in scanner.pieces() {
+        let start = offset;
+        offset += piece.text.len();
+        match piece.token {
+            Some(token) if from.keyword(piece.text).is_some() => {
+                output.push_str(to.spelling(*token.get_type()).unwrap_or(piece.text));
+            }
+            Some(token) if *token.get_type() == TokenType::Identifier && to.keyword(piece.text).is_some() => {
+                errors.push(TranslateError::KeywordCollision(token.get_line(), piece.text.to_string()).into());
+            }
+            Some(_) => output.push_str(piece.text),
+            None => match &pragma {
+                Some(span) if span.start >= start && span.end <= offset => {
+                    output.push_str(&piece.text[..span.start - start]);
+                    output.push_str(to_name);
+                    output.push_str(&piece.text[span.end - start..]);
+                }
+                _ => output.push_str(piece.text),
+            },
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(output)
+}